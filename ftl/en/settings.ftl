@@ -18,6 +18,13 @@ digital_temperature_controller = Digital temperature controller
     .hover = Digital temperature controller
 
 in_real_time = In real time
+retention = Retention
+retention__rows = Rows
+    .hover = Keep the given number of last rows of each topic
+retention__age = Age
+    .hover = Keep the rows of each topic for the given number of seconds
+retention__memory = Memory
+    .hover = Keep the given number of mebibytes of each topic
 loaded_files = Files
     .hover = Loaded files
 delete_all = Delete all
@@ -108,3 +115,290 @@ time_zone__local = Local
     .hover = Local time
 time_zone__utc = UTC
     .hover = Coordinated universal time
+
+mqtt = MQTT
+    .hover = MQTT broker connection
+mqtt__host = Host
+    .hover = Broker host name or address
+mqtt__port = Port
+    .hover = Broker port
+mqtt__websocket = WebSocket
+    .hover = WebSocket port and path, used by the web version
+mqtt__username = Username
+    .hover = Username, no authentication if empty
+mqtt__password = Password
+    .hover = Password, not saved between sessions
+mqtt__tls = TLS
+    .hover = Connect over TLS
+mqtt__ca = CA certificate
+    .hover = Path to the PEM encoded CA certificate, the platform certificates are used if empty
+mqtt__client_id = Client ID
+    .hover = Client identifier, a unique one is generated for each instance if empty
+mqtt__keep_alive = Keep alive
+    .hover = Keep alive interval, seconds
+mqtt__qos = QoS
+    .hover = Quality of service of the subscription
+mqtt__prefix = Topic prefix
+    .hover = Prefix of all device topics
+mqtt__connect = Connect
+    .hover = Apply the settings and reconnect
+mqtt__reset = Reset
+    .hover = Reset the settings to defaults
+mqtt__connecting = Connecting
+mqtt__connected = Connected
+mqtt__reconnecting = Reconnecting
+mqtt__failed = Failed
+mqtt__attempt = Attempt
+mqtt__last_message = Last message
+mqtt__last_error = Last error
+mqtt__rejected = Rejected { $topic }
+mqtt__seconds_ago = { $seconds } s ago
+mqtt__payloads = Payloads
+    .hover = Decoding of the device topic payloads
+mqtt__topic = Topic
+mqtt__format = Format
+mqtt__value = Value
+format__auto = Auto
+    .hover = Sniff the format from the content
+format__ipc = Arrow IPC
+    .hover = Arrow IPC stream
+format__json = JSON
+    .hover = JSON object, array of objects or newline delimited objects; the fields are mapped to the identifier, timestamp and value columns
+generic_topic = Topic
+    .hover = Generic pane of a discovered topic
+topics = Topics
+    .hover = Topics seen under the subscription
+topics__none = No topics seen yet
+topic__messages = Messages
+topic__rate = Rate
+topic__per_second = { $rate } msg/s
+topic__schema = Schema
+recording = Recording
+    .hover = Record the live topics to parquet files
+recording__directory = Directory
+    .hover = Directory of the recorded files, each topic is written to its own subdirectory
+recording__rotation = Rotation
+    .hover = Period after which a new file is started
+rotation__hour = Hour
+    .hover = Start a new file every hour
+rotation__day = Day
+    .hover = Start a new file every day
+replay = Replay
+    .hover = Replay the selected files into the real time panes as if they were received live
+replay__pause = Play or pause
+replay__stop = Stop the replay
+replay__loop = Start over at the end
+replay__speed = Replay speed
+replay__seek = Seek, the real time panes are refilled from the start
+replay__position = Position
+replay__range = Range
+replay__topics = Topics
+simulation = Simulation
+    .hover = Publish synthetic device data to the broker
+simulation__identifiers = Identifiers
+    .hover = Identifiers of the simulated devices
+simulation__rate = Rate
+    .hover = Samples per second
+simulation__noise = Noise
+    .hover = Standard deviation of the noise, fraction of the nominal value
+simulation__drift = Drift
+    .hover = Drift per hour, fraction of the nominal value
+simulation__dropout = Dropout
+    .hover = Probability to drop a sample
+simulation__step = Step
+    .hover = Step change, fraction of the nominal value, and its period; a zero period disables it
+simulation__start = Start
+    .hover = Start publishing with the current settings
+simulation__stop = Stop
+    .hover = Stop publishing
+simulation__published = Published: { $count }
+control = Control
+    .hover = Publish setpoints and commands to the device controllers
+control__device = Device
+control__operator = Operator
+    .hover = Name written to the audit log, the client identifier if empty
+control__identifier = Identifier
+    .hover = Identifier of the controlled device
+control__command = Command
+    .hover = Command published on the command topic of the device
+control__value = Value
+control__send = Send
+    .hover = Send the command after the confirmation
+control__disconnected = Not connected to the broker
+control__confirm = Send the command?
+control__cancel = Cancel
+control__log = Audit log
+    .hover = Sent commands with their sender, time and acknowledgement
+command__setpoint = Setpoint
+    .hover = Temperature setpoint of the controller
+command__dilution = Dilution
+    .hover = Trigger the dilution
+command__calibrate_zero = Zero calibration
+    .hover = Calibrate the zero point of the sensor
+command__calibrate_span = Span calibration
+    .hover = Calibrate the sensor span at the given saturation
+ack__pending = Waiting for the acknowledgement
+ack__acknowledged = Acknowledged
+ack__rejected = Rejected by the controller
+ack__timeout = No acknowledgement received
+ack__failed = Not published
+command__aeration = Aeration
+    .hover = Oxygen saturation setpoint of the controller
+recipe = Recipe
+    .hover = Time based setpoint profiles published to the controllers
+recipe__add = Add a recipe
+recipe__delete = Delete the recipe
+recipe__none = No recipes
+recipe__name = Name
+recipe__initial = Initial value
+    .hover = Value at the start of the recipe
+recipe__stage = Stage
+recipe__target = Target
+recipe__duration = Duration
+recipe__add_stage = Add a stage
+recipe__start = Start the recipe, the setpoints are published when they change
+recipe__stop = Stop the recipe
+recipe__running = Running: { $name }
+recipe__started = Started
+recipe__elapsed = Elapsed
+recipe__setpoint = Setpoint
+stage__ramp = Ramp
+    .hover = Change linearly to the target over the duration
+stage__hold = Hold
+    .hover = Keep the value for the duration
+stage__step = Step
+    .hover = Jump to the target and keep it for the duration
+alarm = Alarm
+alarms = Alarms
+    .hover = Alarm rules, active alarms and their history
+alarms__unacknowledged = Unacknowledged: { $count }
+alarms__active = Active alarms
+    .hover = Raised alarms which are not cleared
+alarms__acknowledge = Acknowledge
+alarms__clear = Clear, the alarm stays in the history
+alarms__raised = The condition holds
+alarms__returned = The value returned to normal
+alarms__rules = Rules
+alarms__add_rule = Add a rule
+alarms__name = Name
+alarms__topic = Topic
+alarms__identifier = Identifier
+    .hover = Device the rule applies to, all devices if checked
+alarms__all = All
+alarms__column = Column
+    .hover = Value column the rule applies to, all value columns if checked
+alarms__condition = Condition
+alarms__limit = Limit
+    .hover = Limit of the value, of its change per minute or of the silence in seconds
+alarms__hysteresis = Hysteresis
+    .hover = Distance back from the limit the value has to return to clear the alarm
+alarms__duration = Duration
+    .hover = Seconds the condition has to hold before the alarm is raised
+alarms__history = History
+alarms__count = Alarms: { $count }
+alarms__export = Export the history to XLSX
+alarms__delete_history = Delete the history
+alarms__evaluate = Evaluate the rules on the loaded files
+condition__high = High
+    .hover = The value is above the limit
+condition__low = Low
+    .hover = The value is below the limit
+condition__rate = Rate of change
+    .hover = The value changes faster than the limit per minute
+condition__no_data = No data
+    .hover = No data for longer than the limit in seconds
+staleness = Staleness
+    .hover = Flags the devices which stopped publishing
+staleness__factor = Factor
+    .hover = A stream is stale after this many expected intervals without data
+staleness__minimum = Minimum
+    .hover = Minimum silence before a stream is stale
+staleness__sound = Sound
+    .hover = Beep when a stream becomes stale
+staleness__topic = Topic
+staleness__identifier = Identifier
+staleness__cadence = Cadence
+    .hover = Interval between the messages learned from their history
+staleness__configured = Configured
+    .hover = Expected interval between the messages of the topic, the learned one is used if unchecked
+staleness__stale = No data from the device
+staleness__silent = silent for { $seconds } s
+dashboard = Dashboard
+    .hover = Latest values of the devices in large text
+dashboard__window = Window
+    .hover = Window of the min, max and mean before the latest timestamp
+dashboard__minutes = { $count } min
+dashboard__hours = { $count } h
+dashboard__min = Min
+dashboard__max = Max
+dashboard__mean = Mean
+import__delimiter = Delimiter
+    .hover = Separator of the fields
+import__comma = Comma
+import__semicolon = Semicolon
+import__tab = Tab
+import__pipe = Vertical bar
+import__decimal_comma = Decimal comma
+    .hover = The numbers use a comma as the decimal separator, e.g. 3,14
+import__header = Header
+    .hover = The first row contains the column names
+import__format = Timestamp format
+    .hover = Format of the timestamp column
+import__rfc3339 = RFC 3339
+import__naive = Pattern
+import__seconds = Unix time, s
+import__milliseconds = Unix time, ms
+import__time_zone = Time zone
+    .hover = Time zone of the timestamps without an offset
+import__offset = Offset
+import__identifier = Identifier
+    .hover = Column of the device identifiers, if none the given identifier is used for all rows
+import__none = None
+import__timestamp = Timestamp
+import__values = Values
+    .hover = Value columns, each becomes a separate file
+import__name = Name
+    .hover = Name of the value column, the device is recognized by it
+import__preview = Preview
+import__import = Import
+import__skip = Skip
+    .hover = Skip this file
+import__queued = Files in the queue: { $count }
+metadata = Metadata
+    .hover = Key/value metadata of the file
+metadata__rows = Rows
+metadata__columns = Columns
+values = Values
+    .hover = Show the value column in the plot
+columns = Columns
+columns__visible = Visible
+    .hover = Show the column in the table
+columns__up = Up
+    .hover = Move the column to the left
+columns__down = Down
+    .hover = Move the column to the right
+browse__incompatible = The selected files hold different kinds of values and can't be browsed together
+merge = Merge
+    .hover = Merge the selected files into a new entry, ordered and without duplicates
+merge__duplicates = Removed duplicate rows: { $count }
+merge__conflicts = Conflicting identifier and timestamp pairs: { $count }
+resolution__first = Keep first
+    .hover = Conflicting rows keep the value of the first file
+resolution__last = Keep last
+    .hover = Conflicting rows keep the value of the last file
+resolution__drop = Drop
+    .hover = Conflicting rows are dropped
+loader__queued = Queued
+loader__reading = Reading
+loader__parsing = Parsing
+loader__cancel = Cancel loading
+loader__dismiss = Dismiss the error
+recipe__confirm = Start the recipe? Its setpoints are published without asking again
+recipe__run = Start
+recipe__failed = Publishing failed, retrying
+qos__at_most_once = At most once
+    .hover = QoS 0, fire and forget
+qos__at_least_once = At least once
+    .hover = QoS 1, acknowledged delivery
+qos__exactly_once = Exactly once
+    .hover = QoS 2, assured delivery
@@ -18,3 +18,298 @@ digital_temperature_controller = Цифровой контроллер темп
     .hover = Цифровой контроллер температуры
 
 in_real_time = В реальном времени
+
+retention = Хранение
+retention__rows = Строки
+    .hover = Хранить заданное количество последних строк каждого топика
+retention__age = Возраст
+    .hover = Хранить строки каждого топика заданное количество секунд
+retention__memory = Память
+    .hover = Хранить заданное количество мебибайт каждого топика
+
+mqtt = MQTT
+    .hover = Подключение к MQTT брокеру
+mqtt__host = Хост
+    .hover = Имя или адрес брокера
+mqtt__port = Порт
+    .hover = Порт брокера
+mqtt__websocket = WebSocket
+    .hover = Порт и путь WebSocket, используются веб-версией
+mqtt__username = Пользователь
+    .hover = Имя пользователя, без аутентификации, если пусто
+mqtt__password = Пароль
+    .hover = Пароль, не сохраняется между сеансами
+mqtt__tls = TLS
+    .hover = Подключаться по TLS
+mqtt__ca = Сертификат ЦС
+    .hover = Путь к сертификату ЦС в формате PEM, если пусто, используются сертификаты платформы
+mqtt__client_id = ID клиента
+    .hover = Идентификатор клиента, если пусто, для каждого экземпляра создается уникальный
+mqtt__keep_alive = Keep alive
+    .hover = Интервал поддержания соединения, секунды
+mqtt__qos = QoS
+    .hover = Качество обслуживания подписки
+mqtt__prefix = Префикс топиков
+    .hover = Префикс всех топиков устройств
+mqtt__connect = Подключиться
+    .hover = Применить настройки и переподключиться
+mqtt__reset = Сбросить
+    .hover = Сбросить настройки по умолчанию
+mqtt__connecting = Подключение
+mqtt__connected = Подключено
+mqtt__reconnecting = Переподключение
+mqtt__failed = Ошибка
+mqtt__attempt = Попытка
+mqtt__last_message = Последнее сообщение
+mqtt__last_error = Последняя ошибка
+mqtt__rejected = Отклонено { $topic }
+mqtt__seconds_ago = { $seconds } с назад
+mqtt__payloads = Полезная нагрузка
+    .hover = Декодирование сообщений топиков устройств
+mqtt__topic = Топик
+mqtt__format = Формат
+mqtt__value = Значение
+format__auto = Авто
+    .hover = Определить формат по содержимому
+format__ipc = Arrow IPC
+    .hover = Поток Arrow IPC
+format__json = JSON
+    .hover = JSON объект, массив объектов или объекты, разделенные переводом строки; поля сопоставляются столбцам идентификатора, времени и значения
+generic_topic = Топик
+    .hover = Общая панель обнаруженного топика
+topics = Топики
+    .hover = Топики, полученные по подписке
+topics__none = Топики пока не получены
+topic__messages = Сообщения
+topic__rate = Частота
+topic__per_second = { $rate } сообщ./с
+topic__schema = Схема
+recording = Запись
+    .hover = Записывать топики в реальном времени в файлы parquet
+recording__directory = Каталог
+    .hover = Каталог записанных файлов, каждый топик записывается в свой подкаталог
+recording__rotation = Ротация
+    .hover = Период, после которого начинается новый файл
+rotation__hour = Час
+    .hover = Начинать новый файл каждый час
+rotation__day = День
+    .hover = Начинать новый файл каждый день
+replay = Воспроизведение
+    .hover = Воспроизвести выбранные файлы в панелях реального времени, как если бы они были получены вживую
+replay__pause = Воспроизвести или приостановить
+replay__stop = Остановить воспроизведение
+replay__loop = Начинать сначала по окончании
+replay__speed = Скорость воспроизведения
+replay__seek = Перемотка, панели реального времени заполняются заново с начала
+replay__position = Позиция
+replay__range = Диапазон
+replay__topics = Топики
+simulation = Симуляция
+    .hover = Публиковать синтетические данные устройств на брокер
+simulation__identifiers = Идентификаторы
+    .hover = Идентификаторы симулируемых устройств
+simulation__rate = Частота
+    .hover = Отсчетов в секунду
+simulation__noise = Шум
+    .hover = Стандартное отклонение шума, доля номинального значения
+simulation__drift = Дрейф
+    .hover = Дрейф в час, доля номинального значения
+simulation__dropout = Пропуски
+    .hover = Вероятность пропуска отсчета
+simulation__step = Ступенька
+    .hover = Ступенчатое изменение, доля номинального значения, и его период; нулевой период отключает его
+simulation__start = Старт
+    .hover = Начать публикацию с текущими настройками
+simulation__stop = Стоп
+    .hover = Остановить публикацию
+simulation__published = Опубликовано: { $count }
+control = Управление
+    .hover = Публиковать уставки и команды контроллерам устройств
+control__device = Устройство
+control__operator = Оператор
+    .hover = Имя, записываемое в журнал, идентификатор клиента если пусто
+control__identifier = Идентификатор
+    .hover = Идентификатор управляемого устройства
+control__command = Команда
+    .hover = Команда, публикуемая в командный топик устройства
+control__value = Значение
+control__send = Отправить
+    .hover = Отправить команду после подтверждения
+control__disconnected = Нет подключения к брокеру
+control__confirm = Отправить команду?
+control__cancel = Отмена
+control__log = Журнал
+    .hover = Отправленные команды с отправителем, временем и подтверждением
+command__setpoint = Уставка
+    .hover = Уставка температуры контроллера
+command__dilution = Разбавление
+    .hover = Запустить разбавление
+command__calibrate_zero = Калибровка нуля
+    .hover = Откалибровать нулевую точку датчика
+command__calibrate_span = Калибровка диапазона
+    .hover = Откалибровать диапазон датчика при заданном насыщении
+ack__pending = Ожидание подтверждения
+ack__acknowledged = Подтверждено
+ack__rejected = Отклонено контроллером
+ack__timeout = Подтверждение не получено
+ack__failed = Не опубликовано
+command__aeration = Аэрация
+    .hover = Уставка насыщения кислородом контроллера
+recipe = Рецепт
+    .hover = Профили уставок во времени, публикуемые контроллерам
+recipe__add = Добавить рецепт
+recipe__delete = Удалить рецепт
+recipe__none = Нет рецептов
+recipe__name = Название
+recipe__initial = Начальное значение
+    .hover = Значение в начале рецепта
+recipe__stage = Этап
+recipe__target = Цель
+recipe__duration = Длительность
+recipe__add_stage = Добавить этап
+recipe__start = Запустить рецепт, уставки публикуются при изменении
+recipe__stop = Остановить рецепт
+recipe__running = Выполняется: { $name }
+recipe__started = Начат
+recipe__elapsed = Прошло
+recipe__setpoint = Уставка
+stage__ramp = Рампа
+    .hover = Линейно изменять до цели за длительность
+stage__hold = Выдержка
+    .hover = Сохранять значение в течение длительности
+stage__step = Ступенька
+    .hover = Перейти к цели и сохранять её в течение длительности
+alarm = Тревога
+alarms = Тревоги
+    .hover = Правила тревог, активные тревоги и их история
+alarms__unacknowledged = Неподтверждённых: { $count }
+alarms__active = Активные тревоги
+    .hover = Поднятые тревоги, которые не сброшены
+alarms__acknowledge = Подтвердить
+alarms__clear = Сбросить, тревога остаётся в истории
+alarms__raised = Условие выполняется
+alarms__returned = Значение вернулось к норме
+alarms__rules = Правила
+alarms__add_rule = Добавить правило
+alarms__name = Название
+alarms__topic = Топик
+alarms__identifier = Идентификатор
+    .hover = Устройство, к которому применяется правило, все устройства если отмечено
+alarms__all = Все
+alarms__column = Столбец
+    .hover = Столбец значений, к которому применяется правило, все столбцы, если отмечено
+alarms__condition = Условие
+alarms__limit = Предел
+    .hover = Предел значения, его изменения в минуту или отсутствия данных в секундах
+alarms__hysteresis = Гистерезис
+    .hover = Насколько значение должно вернуться от предела, чтобы тревога снялась
+alarms__duration = Длительность
+    .hover = Сколько секунд условие должно выполняться, прежде чем тревога поднимется
+alarms__history = История
+alarms__count = Тревог: { $count }
+alarms__export = Экспортировать историю в XLSX
+alarms__delete_history = Удалить историю
+alarms__evaluate = Применить правила к загруженным файлам
+condition__high = Выше
+    .hover = Значение выше предела
+condition__low = Ниже
+    .hover = Значение ниже предела
+condition__rate = Скорость изменения
+    .hover = Значение меняется быстрее предела в минуту
+condition__no_data = Нет данных
+    .hover = Нет данных дольше предела в секундах
+staleness = Устаревание
+    .hover = Отмечает устройства, которые перестали публиковать данные
+staleness__factor = Множитель
+    .hover = Поток устаревает после стольких ожидаемых интервалов без данных
+staleness__minimum = Минимум
+    .hover = Минимальное отсутствие данных, после которого поток устаревает
+staleness__sound = Звук
+    .hover = Подать сигнал, когда поток устаревает
+staleness__topic = Топик
+staleness__identifier = Идентификатор
+staleness__cadence = Период
+    .hover = Интервал между сообщениями, полученный из их истории
+staleness__configured = Задан
+    .hover = Ожидаемый интервал между сообщениями топика, если не отмечено, используется полученный из истории
+staleness__stale = Нет данных от устройства
+staleness__silent = молчит { $seconds } с
+dashboard = Панель
+    .hover = Последние значения устройств крупным шрифтом
+dashboard__window = Окно
+    .hover = Окно минимума, максимума и среднего перед последней отметкой времени
+dashboard__minutes = { $count } мин
+dashboard__hours = { $count } ч
+dashboard__min = Мин
+dashboard__max = Макс
+dashboard__mean = Среднее
+import__delimiter = Разделитель
+    .hover = Разделитель полей
+import__comma = Запятая
+import__semicolon = Точка с запятой
+import__tab = Табуляция
+import__pipe = Вертикальная черта
+import__decimal_comma = Десятичная запятая
+    .hover = В числах дробная часть отделяется запятой, например 3,14
+import__header = Заголовок
+    .hover = Первая строка содержит названия столбцов
+import__format = Формат времени
+    .hover = Формат столбца отметок времени
+import__rfc3339 = RFC 3339
+import__naive = Шаблон
+import__seconds = Unix время, с
+import__milliseconds = Unix время, мс
+import__time_zone = Часовой пояс
+    .hover = Часовой пояс отметок времени без смещения
+import__offset = Смещение
+import__identifier = Идентификатор
+    .hover = Столбец идентификаторов устройств, если не выбран, для всех строк используется заданный идентификатор
+import__none = Нет
+import__timestamp = Отметка времени
+import__values = Значения
+    .hover = Столбцы значений, каждый становится отдельным файлом
+import__name = Название
+    .hover = Название столбца значений, по нему распознаётся устройство
+import__preview = Предпросмотр
+import__import = Импортировать
+import__skip = Пропустить
+    .hover = Пропустить этот файл
+import__queued = Файлов в очереди: { $count }
+metadata = Метаданные
+    .hover = Метаданные файла ключ/значение
+metadata__rows = Строки
+metadata__columns = Столбцы
+values = Значения
+    .hover = Показывать столбец значений на графике
+columns = Столбцы
+columns__visible = Видимый
+    .hover = Показывать столбец в таблице
+columns__up = Вверх
+    .hover = Переместить столбец влево
+columns__down = Вниз
+    .hover = Переместить столбец вправо
+browse__incompatible = Выбранные файлы содержат разные виды значений и не могут быть просмотрены вместе
+merge = Объединить
+    .hover = Объединить выбранные файлы в новую запись, упорядоченно и без дубликатов
+merge__duplicates = Удалено дубликатов: { $count }
+merge__conflicts = Конфликтующих пар идентификатора и времени: { $count }
+resolution__first = Оставить первое
+    .hover = Для конфликтующих строк сохраняется значение первого файла
+resolution__last = Оставить последнее
+    .hover = Для конфликтующих строк сохраняется значение последнего файла
+resolution__drop = Удалить
+    .hover = Конфликтующие строки удаляются
+loader__queued = В очереди
+loader__reading = Чтение
+loader__parsing = Разбор
+loader__cancel = Отменить загрузку
+loader__dismiss = Скрыть ошибку
+recipe__confirm = Запустить рецепт? Его уставки публикуются без повторного подтверждения
+recipe__run = Запустить
+recipe__failed = Не удалось опубликовать, повтор
+qos__at_most_once = Не более одного раза
+    .hover = QoS 0, без подтверждения
+qos__at_least_once = Хотя бы один раз
+    .hover = QoS 1, доставка с подтверждением
+qos__exactly_once = Ровно один раз
+    .hover = QoS 2, гарантированная доставка
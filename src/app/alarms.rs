@@ -0,0 +1,1107 @@
+use super::{
+    YMDHMS,
+    metadata::{FILE, MetaDataFrame, Metadata},
+    mqtt::{self, TOPICS},
+    topic, values,
+};
+use crate::export::xlsx;
+use anyhow::{Result, ensure};
+use arrow::temporal_conversions::timestamp_ms_to_datetime;
+use chrono::Utc;
+use egui::{
+    Button, Checkbox, CollapsingHeader, ComboBox, Context, DragValue, Grid, Id, RichText,
+    ScrollArea, TextEdit, Ui, Window, util::hash,
+};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::{BELL, CHECK, FLOPPY_DISK, MINUS, PLUS, TRASH, X};
+use indexmap::IndexSet;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tracing::{error, info, instrument, warn};
+
+const IDENTIFIER: &str = "Identifier";
+const TIMESTAMP: &str = "Timestamp";
+const BANDS: &str = "AlarmBands";
+const LIMITS: &str = "AlarmLimits";
+const MAX_HISTORY: usize = 10_000;
+const MILLISECONDS_PER_SECOND: f64 = 1000.0;
+const MILLISECONDS_PER_MINUTE: f64 = 60_000.0;
+
+/// Rules created within the same clock tick still get distinct identifiers
+static RULES: AtomicU64 = AtomicU64::new(0);
+
+/// Alarms
+///
+/// Evaluates the alarm rules on the live topic buffers and on the loaded
+/// frames and keeps the history of the raised alarms.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct Alarms {
+    pub(crate) rules: Vec<Rule>,
+    pub(crate) history: Vec<Alarm>,
+    #[serde(skip)]
+    pub(crate) open: bool,
+    /// Live rule states by rule, device identifier and value column
+    #[serde(skip)]
+    trackers: HashMap<(u64, u64, PlSmallStr), Tracker>,
+    /// Last evaluated buffer version and timestamps by topic
+    #[serde(skip)]
+    cursors: HashMap<String, Cursor>,
+    /// Bands are republished for the plot panes when set
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl Alarms {
+    /// Number of the active alarms which are not acknowledged
+    pub(crate) fn unacknowledged(&self) -> usize {
+        self.history
+            .iter()
+            .filter(|alarm| alarm.end.is_none() && !alarm.acknowledged && !alarm.cleared)
+            .count()
+    }
+
+    /// Evaluates the rules on the new rows of the live topic buffers
+    pub(crate) fn update(&mut self, context: &Context) {
+        let topics = self
+            .rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(|rule| rule.topic.clone())
+            .collect::<BTreeSet<_>>();
+        for topic in topics {
+            if let Err(error) = self.update_topic(context, &topic) {
+                warn!(%topic, %error);
+            }
+        }
+        // No data
+        let now = Utc::now().timestamp_millis();
+        for ((id, identifier, column), tracker) in &mut self.trackers {
+            let Some(rule) = self.rules.iter().find(|rule| rule.id == *id) else {
+                continue;
+            };
+            if rule.enabled && rule.condition == Condition::NoData {
+                for event in tracker.silence(rule, now) {
+                    apply(
+                        &mut self.history,
+                        rule,
+                        *identifier,
+                        column,
+                        Source::Live,
+                        event,
+                    );
+                    self.changed = true;
+                }
+            }
+        }
+        // Limits are published once after the restore as well
+        let published =
+            context.data(|data| data.get_temp::<Arc<Vec<Rule>>>(Id::new(LIMITS)).is_some());
+        if self.changed || !published {
+            self.changed = false;
+            self.bands(context);
+            self.limits(context);
+        }
+    }
+
+    fn update_topic(&mut self, context: &Context, topic: &str) -> Result<()> {
+        let Some(buffer) = mqtt::buffer(context, topic) else {
+            return Ok(());
+        };
+        let mut buffer = buffer.lock();
+        let cursor = self.cursors.entry(topic.to_owned()).or_default();
+        if buffer.is_empty() || cursor.version == Some(buffer.version()) {
+            return Ok(());
+        }
+        // Only the new batches, unless the buffer was cleared, e.g. by a
+        // replay seek or loop, and restarts at older timestamps
+        let appended = match cursor.version {
+            Some(version) => buffer.appended(version)?,
+            None => None,
+        };
+        let data = match appended {
+            Some(data) => data,
+            None => {
+                cursor.timestamps.clear();
+                self.trackers.retain(|(id, ..), _| {
+                    self.rules
+                        .iter()
+                        .all(|rule| rule.id != *id || rule.topic != topic)
+                });
+                buffer.data_frame()?
+            }
+        };
+        cursor.version = Some(buffer.version());
+        drop(buffer);
+        let samples = samples(&data)?
+            .into_iter()
+            .filter(|sample| {
+                cursor
+                    .timestamps
+                    .get(&sample.identifier)
+                    .is_none_or(|last| sample.timestamp > *last)
+            })
+            .collect::<Vec<_>>();
+        for sample in &samples {
+            cursor
+                .timestamps
+                .entry(sample.identifier)
+                .and_modify(|last| *last = (*last).max(sample.timestamp))
+                .or_insert(sample.timestamp);
+        }
+        let now = Utc::now().timestamp_millis();
+        for rule in &self.rules {
+            if !rule.enabled || rule.topic != topic {
+                continue;
+            }
+            for sample in samples
+                .iter()
+                .filter(|sample| rule.applies(sample.identifier, &sample.column))
+            {
+                let tracker = self
+                    .trackers
+                    .entry((rule.id, sample.identifier, sample.column.clone()))
+                    .or_default();
+                tracker.received = Some(now);
+                for event in tracker.feed(rule, sample.timestamp, sample.value) {
+                    apply(
+                        &mut self.history,
+                        rule,
+                        sample.identifier,
+                        &sample.column,
+                        Source::Live,
+                        event,
+                    );
+                    self.changed = true;
+                }
+            }
+        }
+        truncate(&mut self.history);
+        Ok(())
+    }
+
+    /// Evaluates the rules on the loaded frame, replacing its previous alarms
+    #[instrument(skip_all, err)]
+    pub(crate) fn evaluate(&mut self, frame: &MetaDataFrame) -> Result<()> {
+        // A wide frame holds the values of several topics
+        let topics = values(&frame.data)
+            .map(|column| topic(column.name()))
+            .collect::<BTreeSet<_>>();
+        let file = frame.meta.get(FILE).cloned().unwrap_or_default();
+        let source = Source::File(file);
+        self.history.retain(|alarm| alarm.source != source);
+        self.changed = true;
+        if !self
+            .rules
+            .iter()
+            .any(|rule| rule.enabled && topics.contains(&rule.topic))
+        {
+            return Ok(());
+        }
+        let samples = samples(&frame.data)?;
+        for rule in &self.rules {
+            if !rule.enabled || !topics.contains(&rule.topic) {
+                continue;
+            }
+            let mut trackers = BTreeMap::<_, Tracker>::new();
+            for sample in samples.iter().filter(|sample| {
+                rule.applies(sample.identifier, &sample.column)
+                    && topic(&sample.column) == rule.topic
+            }) {
+                let tracker = trackers
+                    .entry((sample.identifier, sample.column.clone()))
+                    .or_default();
+                for event in tracker.feed(rule, sample.timestamp, sample.value) {
+                    apply(
+                        &mut self.history,
+                        rule,
+                        sample.identifier,
+                        &sample.column,
+                        source.clone(),
+                        event,
+                    );
+                }
+            }
+        }
+        info!(?source, alarms = self.history.len(), "Evaluated");
+        truncate(&mut self.history);
+        Ok(())
+    }
+
+    /// Publishes the alarm intervals for the plot panes
+    fn bands(&self, context: &Context) {
+        let bands = self
+            .history
+            .iter()
+            .map(|alarm| Band {
+                topic: alarm.topic.clone(),
+                start: alarm.start,
+                end: alarm.end,
+            })
+            .collect::<Vec<_>>();
+        context.data_mut(|data| data.insert_temp(Id::new(BANDS), Arc::new(bands)));
+    }
+
+    /// Publishes the value limit rules for the dashboard panes
+    fn limits(&self, context: &Context) {
+        let limits = self
+            .rules
+            .iter()
+            .filter(|rule| {
+                rule.enabled && matches!(rule.condition, Condition::High | Condition::Low)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        context.data_mut(|data| data.insert_temp(Id::new(LIMITS), Arc::new(limits)));
+    }
+
+    /// Forgets the live rule states, the rules changed
+    fn reset(&mut self) {
+        self.trackers.clear();
+        self.changed = true;
+    }
+}
+
+impl Alarms {
+    pub(crate) fn window(&mut self, context: &Context, frames: &IndexSet<MetaDataFrame>) {
+        let mut open = self.open;
+        Window::new(format!("{BELL} Alarms"))
+            .open(&mut open)
+            .show(context, |ui| self.show(ui, frames));
+        self.open = open;
+    }
+
+    fn show(&mut self, ui: &mut Ui, frames: &IndexSet<MetaDataFrame>) {
+        // Active
+        ui.heading(ui.localize("alarms__active"))
+            .on_hover_localized("alarms__active.hover");
+        let mut changed = false;
+        ScrollArea::vertical()
+            .id_salt("Active")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+                    for alarm in self.history.iter_mut().rev().filter(|alarm| !alarm.cleared) {
+                        alarm.show(ui);
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(!alarm.acknowledged, Button::new(CHECK))
+                                .on_hover_localized("alarms__acknowledge")
+                                .clicked()
+                            {
+                                alarm.acknowledged = true;
+                            }
+                            if ui.button(X).on_hover_localized("alarms__clear").clicked() {
+                                alarm.cleared = true;
+                                changed = true;
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+        ui.separator();
+        // Rules
+        CollapsingHeader::new(ui.localize("alarms__rules"))
+            .id_salt("Rules")
+            .show(ui, |ui| {
+                if self.show_rules(ui) {
+                    self.reset();
+                }
+            });
+        // History
+        CollapsingHeader::new(ui.localize("alarms__history"))
+            .id_salt("History")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(ui.localize(&format!("alarms__count?count={}", self.history.len())));
+                    if ui
+                        .button(FLOPPY_DISK)
+                        .on_hover_localized("alarms__export")
+                        .clicked()
+                    {
+                        if let Err(error) = self.export() {
+                            error!(%error);
+                        }
+                    }
+                    if ui
+                        .button(TRASH)
+                        .on_hover_localized("alarms__delete_history")
+                        .clicked()
+                    {
+                        self.history.clear();
+                        self.trackers.clear();
+                        changed = true;
+                    }
+                    if ui
+                        .add_enabled(!frames.is_empty(), Button::new(BELL))
+                        .on_hover_localized("alarms__evaluate")
+                        .clicked()
+                    {
+                        for frame in frames {
+                            self.evaluate(frame).ok();
+                        }
+                    }
+                });
+                ScrollArea::vertical()
+                    .id_salt("History")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+                            for alarm in self.history.iter().rev() {
+                                alarm.show(ui);
+                                ui.end_row();
+                            }
+                        });
+                    });
+            });
+        if changed {
+            self.bands(ui.ctx());
+        }
+    }
+
+    /// Shows the rule editor, returns whether the rules changed
+    fn show_rules(&mut self, ui: &mut Ui) -> bool {
+        let mut topics = TOPICS.map(ToOwned::to_owned).to_vec();
+        for (topic, _) in mqtt::topics(ui.ctx()).lock().iter() {
+            if !topics.contains(topic) {
+                topics.push(topic.clone());
+            }
+        }
+        let mut changed = false;
+        let mut remove = None;
+        Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label(ui.localize("alarms__name"));
+            ui.label(ui.localize("alarms__topic"));
+            ui.label(ui.localize("alarms__identifier"))
+                .on_hover_localized("alarms__identifier.hover");
+            ui.label(ui.localize("alarms__column"))
+                .on_hover_localized("alarms__column.hover");
+            ui.label(ui.localize("alarms__condition"));
+            ui.label(ui.localize("alarms__limit"))
+                .on_hover_localized("alarms__limit.hover");
+            ui.label(ui.localize("alarms__hysteresis"))
+                .on_hover_localized("alarms__hysteresis.hover");
+            ui.label(ui.localize("alarms__duration"))
+                .on_hover_localized("alarms__duration.hover");
+            ui.end_row();
+            for (index, rule) in self.rules.iter_mut().enumerate() {
+                let previous = rule.clone();
+                ui.add(Checkbox::without_text(&mut rule.enabled));
+                ui.text_edit_singleline(&mut rule.name);
+                ComboBox::from_id_salt(ui.id().with(("Topic", index)))
+                    .selected_text(&rule.topic)
+                    .show_ui(ui, |ui| {
+                        for topic in &topics {
+                            ui.selectable_value(&mut rule.topic, topic.clone(), topic);
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    let mut all = rule.identifier.is_none();
+                    if ui.checkbox(&mut all, ui.localize("alarms__all")).changed() {
+                        rule.identifier = if all { None } else { Some(1) };
+                    }
+                    if let Some(identifier) = &mut rule.identifier {
+                        ui.add(DragValue::new(identifier).hexadecimal(1, false, false));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let mut all = rule.column.is_none();
+                    if ui.checkbox(&mut all, ui.localize("alarms__all")).changed() {
+                        rule.column = if all {
+                            None
+                        } else {
+                            Some(mqtt::name(&rule.topic).unwrap_or_default().to_owned())
+                        };
+                    }
+                    if let Some(column) = &mut rule.column {
+                        ui.add(TextEdit::singleline(column).desired_width(120.0));
+                    }
+                });
+                ComboBox::from_id_salt(ui.id().with(("Condition", index)))
+                    .selected_text(ui.localize(rule.condition.text()))
+                    .show_ui(ui, |ui| {
+                        for condition in [
+                            Condition::High,
+                            Condition::Low,
+                            Condition::Rate,
+                            Condition::NoData,
+                        ] {
+                            ui.selectable_value(
+                                &mut rule.condition,
+                                condition,
+                                ui.localize(condition.text()),
+                            )
+                            .on_hover_localized(condition.hover_text());
+                        }
+                    })
+                    .response
+                    .on_hover_localized(rule.condition.hover_text());
+                ui.add(
+                    DragValue::new(&mut rule.limit)
+                        .speed(0.1)
+                        .suffix(rule.condition.unit()),
+                );
+                ui.add_enabled(
+                    rule.condition != Condition::NoData,
+                    DragValue::new(&mut rule.hysteresis)
+                        .range(0.0..=f64::MAX)
+                        .speed(0.1),
+                );
+                ui.add_enabled(
+                    rule.condition != Condition::NoData,
+                    DragValue::new(&mut rule.duration)
+                        .range(0.0..=f64::MAX)
+                        .suffix(" s"),
+                );
+                if ui.button(MINUS).clicked() {
+                    remove = Some(index);
+                }
+                ui.end_row();
+                changed |= *rule != previous;
+            }
+        });
+        if let Some(index) = remove {
+            self.rules.remove(index);
+            changed = true;
+        }
+        if ui
+            .button(PLUS)
+            .on_hover_localized("alarms__add_rule")
+            .clicked()
+        {
+            self.rules.push(Rule::new());
+            changed = true;
+        }
+        changed
+    }
+
+    #[instrument(skip(self), err)]
+    fn export(&self) -> Result<()> {
+        let mut rules = Vec::with_capacity(self.history.len());
+        let mut sources = Vec::with_capacity(self.history.len());
+        let mut topics = Vec::with_capacity(self.history.len());
+        let mut identifiers = Vec::with_capacity(self.history.len());
+        let mut columns = Vec::with_capacity(self.history.len());
+        let mut conditions = Vec::with_capacity(self.history.len());
+        let mut values = Vec::with_capacity(self.history.len());
+        let mut starts = Vec::with_capacity(self.history.len());
+        let mut ends = Vec::with_capacity(self.history.len());
+        let mut acknowledged = Vec::with_capacity(self.history.len());
+        for alarm in &self.history {
+            rules.push(alarm.rule.clone());
+            sources.push(alarm.source.to_string());
+            topics.push(alarm.topic.clone());
+            identifiers.push(alarm.identifier);
+            columns.push(alarm.column.clone());
+            conditions.push(format!("{:?}", alarm.condition));
+            values.push(alarm.value);
+            starts.push(alarm.start);
+            ends.push(alarm.end);
+            acknowledged.push(alarm.acknowledged);
+        }
+        let data_frame = df! {
+            "Rule" => rules,
+            "Source" => sources,
+            "Topic" => topics,
+            IDENTIFIER => identifiers,
+            "Column" => columns,
+            "Condition" => conditions,
+            "Value" => values,
+            "Start" => starts,
+            "End" => ends,
+            "Acknowledged" => acknowledged,
+        }?
+        .lazy()
+        .with_columns([
+            col("Start").cast(DataType::Datetime(TimeUnit::Milliseconds, None)),
+            col("End").cast(DataType::Datetime(TimeUnit::Milliseconds, None)),
+        ])
+        .collect()?;
+        xlsx::save(&data_frame, &Metadata::new(), "alarms.xlsx")
+    }
+}
+
+/// Alarm rule
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Rule {
+    /// Stable identifier, the name is editable
+    pub(crate) id: u64,
+    pub(crate) enabled: bool,
+    pub(crate) name: String,
+    /// Topic relative to the prefix
+    pub(crate) topic: String,
+    /// Identifier of the device, all devices if none
+    pub(crate) identifier: Option<u64>,
+    /// Value column, all value columns of the topic if none
+    pub(crate) column: Option<String>,
+    pub(crate) condition: Condition,
+    /// Limit of the value, of its change per minute or of the silence in
+    /// seconds
+    pub(crate) limit: f64,
+    /// Distance back from the limit the value has to return to clear the alarm
+    pub(crate) hysteresis: f64,
+    /// Seconds the condition has to hold before the alarm is raised
+    pub(crate) duration: f64,
+}
+
+impl Rule {
+    pub(crate) fn new() -> Self {
+        Self {
+            id: hash((
+                Utc::now().timestamp_nanos_opt(),
+                RULES.fetch_add(1, Ordering::Relaxed),
+            )),
+            enabled: true,
+            name: "Alarm".to_owned(),
+            topic: mqtt::TOPIC_DTEC.to_owned(),
+            identifier: None,
+            column: None,
+            condition: Condition::High,
+            limit: 40.0,
+            hysteresis: 0.5,
+            duration: 10.0,
+        }
+    }
+
+    fn applies(&self, identifier: u64, column: &str) -> bool {
+        self.identifier
+            .is_none_or(|expected| expected == identifier)
+            && self
+                .column
+                .as_ref()
+                .is_none_or(|expected| expected.eq_ignore_ascii_case(column))
+    }
+
+    fn raises(&self, measure: f64) -> bool {
+        match self.condition {
+            Condition::High => measure > self.limit,
+            Condition::Low => measure < self.limit,
+            Condition::Rate => measure.abs() > self.limit,
+            Condition::NoData => false,
+        }
+    }
+
+    fn clears(&self, measure: f64) -> bool {
+        match self.condition {
+            Condition::High => measure <= self.limit - self.hysteresis,
+            Condition::Low => measure >= self.limit + self.hysteresis,
+            Condition::Rate => measure.abs() <= self.limit - self.hysteresis,
+            Condition::NoData => true,
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Alarm condition
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Condition {
+    #[default]
+    High,
+    Low,
+    /// Change per minute
+    Rate,
+    /// No data for the given seconds
+    NoData,
+}
+
+impl Condition {
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::High => "condition__high",
+            Self::Low => "condition__low",
+            Self::Rate => "condition__rate",
+            Self::NoData => "condition__no_data",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::High => "condition__high.hover",
+            Self::Low => "condition__low.hover",
+            Self::Rate => "condition__rate.hover",
+            Self::NoData => "condition__no_data.hover",
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            Self::High | Self::Low => "",
+            Self::Rate => " /min",
+            Self::NoData => " s",
+        }
+    }
+}
+
+/// Raised alarm
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct Alarm {
+    /// Identifier of the rule
+    #[serde(default)]
+    pub(crate) id: u64,
+    /// Name of the rule when raised
+    pub(crate) rule: String,
+    pub(crate) source: Source,
+    pub(crate) topic: String,
+    pub(crate) identifier: u64,
+    /// Value column
+    #[serde(default)]
+    pub(crate) column: String,
+    pub(crate) condition: Condition,
+    /// Measure which raised the alarm
+    pub(crate) value: f64,
+    /// Milliseconds
+    pub(crate) start: i64,
+    /// Milliseconds, none while the condition holds
+    pub(crate) end: Option<i64>,
+    pub(crate) acknowledged: bool,
+    /// Removed from the active alarms
+    pub(crate) cleared: bool,
+}
+
+impl Alarm {
+    fn matches(&self, rule: &Rule, identifier: u64, column: &str, source: &Source) -> bool {
+        self.id == rule.id
+            && self.topic == rule.topic
+            && self.identifier == identifier
+            && self.column == column
+            && &self.source == source
+    }
+}
+
+impl Alarm {
+    fn show(&self, ui: &mut Ui) {
+        let color = if self.end.is_some() {
+            ui.visuals().warn_fg_color
+        } else {
+            ui.visuals().error_fg_color
+        };
+        ui.label(RichText::new(BELL).color(color))
+            .on_hover_localized(if self.end.is_some() {
+                "alarms__returned"
+            } else {
+                "alarms__raised"
+            });
+        ui.label(format(self.start));
+        ui.label(self.end.map_or_else(|| "-".to_owned(), format));
+        ui.label(&self.rule).on_hover_text(self.source.to_string());
+        ui.label(format!("{} {:x}", self.topic, self.identifier))
+            .on_hover_text(&self.column);
+        ui.label(format!(
+            "{} {:.3}{}",
+            ui.localize(self.condition.text()),
+            self.value,
+            self.condition.unit(),
+        ));
+    }
+}
+
+/// Alarm source
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Source {
+    Live,
+    /// Loaded file
+    File(String),
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Live => f.write_str("Live"),
+            Self::File(file) => f.write_str(file),
+        }
+    }
+}
+
+/// Alarm interval drawn on the plot panes
+#[derive(Clone, Debug)]
+pub(crate) struct Band {
+    pub(crate) topic: String,
+    pub(crate) start: i64,
+    pub(crate) end: Option<i64>,
+}
+
+/// Alarm intervals for the plot panes
+pub(crate) fn bands(context: &Context) -> Arc<Vec<Band>> {
+    context
+        .data(|data| data.get_temp(Id::new(BANDS)))
+        .unwrap_or_default()
+}
+
+/// Alarm level of the value against the limit rules of the topic, device and
+/// value column
+pub(crate) fn level(
+    context: &Context,
+    topic: &str,
+    identifier: u64,
+    column: &str,
+    value: f64,
+) -> Level {
+    let limits = context
+        .data(|data| data.get_temp::<Arc<Vec<Rule>>>(Id::new(LIMITS)))
+        .unwrap_or_default();
+    limits
+        .iter()
+        .filter(|rule| rule.topic == topic && rule.applies(identifier, column))
+        .map(|rule| {
+            if rule.raises(value) {
+                Level::Alarm
+            } else if !rule.clears(value) {
+                Level::Warning
+            } else {
+                Level::Normal
+            }
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+/// Alarm level
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub(crate) enum Level {
+    #[default]
+    Normal,
+    /// Within the hysteresis of the limit
+    Warning,
+    Alarm,
+}
+
+/// Rule state of a device
+#[derive(Clone, Debug, Default)]
+struct Tracker {
+    /// Time since the condition holds
+    since: Option<i64>,
+    active: bool,
+    /// Previous sample, for the rate
+    previous: Option<(i64, f64)>,
+    /// Wall clock time of the previous live sample, for the silence
+    received: Option<i64>,
+}
+
+impl Tracker {
+    fn feed(&mut self, rule: &Rule, timestamp: i64, value: f64) -> Vec<Event> {
+        let previous = self.previous.replace((timestamp, value));
+        let measure = match rule.condition {
+            Condition::High | Condition::Low => value,
+            Condition::Rate => match previous {
+                Some((time, previous)) if timestamp > time => {
+                    (value - previous) / (timestamp - time) as f64 * MILLISECONDS_PER_MINUTE
+                }
+                _ => return Vec::new(),
+            },
+            Condition::NoData => {
+                let mut events = Vec::new();
+                // The gap is over
+                if let Some((time, _)) = previous {
+                    let limit = (rule.limit * MILLISECONDS_PER_SECOND) as i64;
+                    if !self.active && timestamp - time > limit {
+                        self.active = true;
+                        events.push(Event::Raise {
+                            timestamp: time + limit,
+                            value: (timestamp - time) as f64 / MILLISECONDS_PER_SECOND,
+                        });
+                    }
+                }
+                if self.active {
+                    self.active = false;
+                    events.push(Event::Clear { timestamp });
+                }
+                return events;
+            }
+        };
+        if self.active {
+            if rule.clears(measure) {
+                self.active = false;
+                self.since = None;
+                return vec![Event::Clear { timestamp }];
+            }
+        } else if rule.raises(measure) {
+            let since = *self.since.get_or_insert(timestamp);
+            if (timestamp - since) as f64 >= rule.duration * MILLISECONDS_PER_SECOND {
+                self.active = true;
+                return vec![Event::Raise {
+                    timestamp: since,
+                    value: measure,
+                }];
+            }
+        } else {
+            self.since = None;
+        }
+        Vec::new()
+    }
+
+    /// Raises the no data alarm when the last sample was received longer than
+    /// the limit ago. The receipt time is used, the sample timestamps of the
+    /// replayed data or of a skewed device clock are far from now.
+    fn silence(&mut self, rule: &Rule, now: i64) -> Vec<Event> {
+        let (Some(received), Some((timestamp, _))) = (self.received, self.previous) else {
+            return Vec::new();
+        };
+        let limit = (rule.limit * MILLISECONDS_PER_SECOND) as i64;
+        let silence = now - received;
+        if self.active || silence <= limit {
+            return Vec::new();
+        }
+        self.active = true;
+        vec![Event::Raise {
+            timestamp: timestamp + limit,
+            value: silence as f64 / MILLISECONDS_PER_SECOND,
+        }]
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Event {
+    Raise { timestamp: i64, value: f64 },
+    Clear { timestamp: i64 },
+}
+
+/// Evaluated buffer state of a topic
+#[derive(Clone, Debug, Default)]
+struct Cursor {
+    version: Option<u64>,
+    /// Last evaluated timestamp by device identifier
+    timestamps: HashMap<u64, i64>,
+}
+
+#[derive(Clone, Debug)]
+struct Sample {
+    identifier: u64,
+    column: PlSmallStr,
+    timestamp: i64,
+    value: f64,
+}
+
+fn apply(
+    history: &mut Vec<Alarm>,
+    rule: &Rule,
+    identifier: u64,
+    column: &str,
+    source: Source,
+    event: Event,
+) {
+    match event {
+        Event::Raise { timestamp, value } => {
+            // Still open since the previous session
+            if history.iter().any(|alarm| {
+                alarm.end.is_none() && alarm.matches(rule, identifier, column, &source)
+            }) {
+                return;
+            }
+            info!(rule = %rule.name, identifier, column, value, "Alarm raised");
+            history.push(Alarm {
+                id: rule.id,
+                rule: rule.name.clone(),
+                source,
+                topic: rule.topic.clone(),
+                identifier,
+                column: column.to_owned(),
+                condition: rule.condition,
+                value,
+                start: timestamp,
+                end: None,
+                acknowledged: false,
+                cleared: false,
+            });
+        }
+        Event::Clear { timestamp } => {
+            if let Some(alarm) = history.iter_mut().rev().find(|alarm| {
+                alarm.end.is_none() && alarm.matches(rule, identifier, column, &source)
+            }) {
+                info!(rule = %rule.name, identifier, column, "Alarm returned");
+                alarm.end = Some(timestamp);
+            }
+        }
+    }
+}
+
+fn truncate(history: &mut Vec<Alarm>) {
+    if history.len() > MAX_HISTORY {
+        history.drain(..history.len() - MAX_HISTORY);
+    }
+}
+
+/// Identifier, timestamp and value rows of every numeric value column, sorted
+/// by the timestamp
+fn samples(data: &DataFrame) -> Result<Vec<Sample>> {
+    ensure!(values(data).next().is_some(), "No numeric value column");
+    let identifiers = data.column(IDENTIFIER)?.cast(&DataType::UInt64)?;
+    let timestamps = data
+        .column(TIMESTAMP)?
+        .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?
+        .cast(&DataType::Int64)?;
+    let mut samples = Vec::new();
+    for column in values(data) {
+        let numbers = column.cast(&DataType::Float64)?;
+        samples.extend(
+            identifiers
+                .u64()?
+                .iter()
+                .zip(timestamps.i64()?)
+                .zip(numbers.f64()?)
+                .filter_map(|((identifier, timestamp), value)| {
+                    Some(Sample {
+                        identifier: identifier?,
+                        column: column.name().clone(),
+                        timestamp: timestamp?,
+                        value: value?,
+                    })
+                }),
+        );
+    }
+    samples.sort_by_key(|sample| sample.timestamp);
+    Ok(samples)
+}
+
+fn format(timestamp: i64) -> String {
+    timestamp_ms_to_datetime(timestamp).map_or_else(String::new, |timestamp| {
+        timestamp.format(YMDHMS).to_string()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rule(condition: Condition, limit: f64, hysteresis: f64, duration: f64) -> Rule {
+        Rule {
+            condition,
+            limit,
+            hysteresis,
+            duration,
+            ..Rule::new()
+        }
+    }
+
+    fn raised(events: &[Event]) -> Vec<(i64, f64)> {
+        events
+            .iter()
+            .filter_map(|event| match *event {
+                Event::Raise { timestamp, value } => Some((timestamp, value)),
+                Event::Clear { .. } => None,
+            })
+            .collect()
+    }
+
+    fn cleared(events: &[Event]) -> Vec<i64> {
+        events
+            .iter()
+            .filter_map(|event| match *event {
+                Event::Clear { timestamp } => Some(timestamp),
+                Event::Raise { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn column() {
+        let mut rule = rule(Condition::High, 10.0, 1.0, 0.0);
+        assert!(rule.applies(1, "V1"));
+        assert!(rule.applies(1, "T1"));
+        rule.column = Some("V1".to_owned());
+        assert!(rule.applies(1, "v1"));
+        assert!(!rule.applies(1, "T1"));
+        rule.identifier = Some(2);
+        assert!(!rule.applies(1, "V1"));
+        assert!(rule.applies(2, "V1"));
+    }
+
+    #[test]
+    fn hysteresis() {
+        let rule = rule(Condition::High, 10.0, 1.0, 0.0);
+        let mut tracker = Tracker::default();
+        assert!(tracker.feed(&rule, 0, 9.0).is_empty());
+        assert_eq!(raised(&tracker.feed(&rule, 1000, 11.0)), [(1000, 11.0)]);
+        // Within the hysteresis the alarm holds
+        assert!(tracker.feed(&rule, 2000, 9.5).is_empty());
+        assert!(tracker.feed(&rule, 3000, 12.0).is_empty());
+        assert_eq!(cleared(&tracker.feed(&rule, 4000, 9.0)), [4000]);
+        assert!(tracker.feed(&rule, 5000, 9.5).is_empty());
+    }
+
+    #[test]
+    fn low() {
+        let rule = rule(Condition::Low, 5.0, 1.0, 0.0);
+        let mut tracker = Tracker::default();
+        assert_eq!(raised(&tracker.feed(&rule, 0, 4.0)), [(0, 4.0)]);
+        assert!(tracker.feed(&rule, 1000, 5.5).is_empty());
+        assert_eq!(cleared(&tracker.feed(&rule, 2000, 6.0)), [2000]);
+    }
+
+    #[test]
+    fn duration() {
+        let rule = rule(Condition::High, 10.0, 0.0, 10.0);
+        let mut tracker = Tracker::default();
+        assert!(tracker.feed(&rule, 0, 11.0).is_empty());
+        assert!(tracker.feed(&rule, 5000, 11.0).is_empty());
+        // An interruption restarts the duration
+        assert!(tracker.feed(&rule, 6000, 9.0).is_empty());
+        assert!(tracker.feed(&rule, 7000, 11.0).is_empty());
+        assert!(tracker.feed(&rule, 16_000, 12.0).is_empty());
+        // Raised at the start of the condition
+        assert_eq!(raised(&tracker.feed(&rule, 17_000, 12.0)), [(7000, 12.0)]);
+    }
+
+    #[test]
+    fn rate() {
+        let rule = rule(Condition::Rate, 2.0, 0.5, 0.0);
+        let mut tracker = Tracker::default();
+        // No rate without the previous sample
+        assert!(tracker.feed(&rule, 0, 0.0).is_empty());
+        // 1 per minute
+        assert!(tracker.feed(&rule, 60_000, 1.0).is_empty());
+        // -3 per minute
+        let events = raised(&tracker.feed(&rule, 120_000, -2.0));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, 120_000);
+        assert!((events[0].1 + 3.0).abs() < 1e-9);
+        // The same timestamp has no rate
+        assert!(tracker.feed(&rule, 120_000, 5.0).is_empty());
+        assert_eq!(cleared(&tracker.feed(&rule, 180_000, 6.0)), [180_000]);
+    }
+
+    #[test]
+    fn gap() {
+        let rule = rule(Condition::NoData, 10.0, 0.0, 0.0);
+        let mut tracker = Tracker::default();
+        assert!(tracker.feed(&rule, 0, 1.0).is_empty());
+        assert!(tracker.feed(&rule, 10_000, 1.0).is_empty());
+        // A gap longer than the limit is raised and cleared by the same sample
+        let events = tracker.feed(&rule, 25_000, 1.0);
+        assert_eq!(raised(&events), [(20_000, 15.0)]);
+        assert_eq!(cleared(&events), [25_000]);
+    }
+
+    #[test]
+    fn silence() {
+        let rule = rule(Condition::NoData, 10.0, 0.0, 0.0);
+        let mut tracker = Tracker::default();
+        // Nothing received yet
+        assert!(tracker.silence(&rule, 1_000_000).is_empty());
+        // Historical data, received now
+        tracker.received = Some(1_000_000);
+        assert!(tracker.feed(&rule, 0, 1.0).is_empty());
+        assert!(tracker.silence(&rule, 1_005_000).is_empty());
+        assert_eq!(raised(&tracker.silence(&rule, 1_015_000)), [(10_000, 15.0)]);
+        // Raised once
+        assert!(tracker.silence(&rule, 1_020_000).is_empty());
+        // The next sample clears it
+        tracker.received = Some(1_021_000);
+        assert_eq!(cleared(&tracker.feed(&rule, 21_000, 1.0)), [21_000]);
+        assert!(tracker.silence(&rule, 1_025_000).is_empty());
+    }
+}
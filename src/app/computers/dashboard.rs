@@ -0,0 +1,147 @@
+use crate::{
+    app::{metadata::MetaDataFrame, panes::settings::Settings},
+    utils::hashed::Hashed,
+};
+use egui::util::cache::{ComputerMut, FrameCache};
+use polars::prelude::*;
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+const IDENTIFIER: &str = "Identifier";
+const TIMESTAMP: &str = "Timestamp";
+const VALUE: &str = "Value";
+const MINUTE: i64 = 60 * 1000;
+const SPARKLINE: i64 = 60 * MINUTE;
+
+/// Dashboard computed
+pub(in crate::app) type Computed = FrameCache<Value, Computer>;
+
+/// Dashboard computer
+#[derive(Default)]
+pub(in crate::app) struct Computer;
+
+impl Computer {
+    #[instrument(skip(self), err)]
+    fn try_compute(&mut self, key: Key) -> PolarsResult<Value> {
+        let lazy_frame = key
+            .frame
+            .data
+            .clone()
+            .lazy()
+            .sort([IDENTIFIER, TIMESTAMP], Default::default());
+        // One series per identifier and value column
+        let mut series = BTreeMap::<_, Vec<[_; 2]>>::new();
+        for name in key.settings.values.selected(&key.frame.data) {
+            let data_frame = lazy_frame
+                .clone()
+                .select([
+                    col(IDENTIFIER),
+                    col(TIMESTAMP).cast(DataType::Int64),
+                    col(name.clone()).cast(DataType::Float64).alias(VALUE),
+                ])
+                .collect()?;
+            for ((identifier, timestamp), value) in data_frame
+                .column(IDENTIFIER)?
+                .u64()?
+                .iter()
+                .zip(data_frame.column(TIMESTAMP)?.i64()?)
+                .zip(data_frame.column(VALUE)?.f64()?)
+            {
+                if let (Some(identifier), Some(timestamp), Some(value)) =
+                    (identifier, timestamp, value)
+                {
+                    series
+                        .entry((identifier, name.to_string()))
+                        .or_default()
+                        .push([timestamp, value]);
+                }
+            }
+        }
+        // Live frames end at the current time, loaded frames at their latest
+        // timestamp
+        let Some(end) = key.now.or_else(|| {
+            series
+                .values()
+                .filter_map(|points| points.last())
+                .map(|&[timestamp, _]| timestamp)
+                .max()
+        }) else {
+            return Ok(Value::default());
+        };
+        let window = end - key.settings.dashboard.window as i64 * MINUTE;
+        let mut value = Value::default();
+        for ((identifier, column), points) in series {
+            let Some(&[timestamp, last]) = points.last() else {
+                continue;
+            };
+            let (mut min, mut max, mut sum, mut count) = (f64::INFINITY, f64::NEG_INFINITY, 0.0, 0);
+            for &[_, value] in points.iter().filter(|&&[timestamp, _]| timestamp >= window) {
+                min = min.min(value);
+                max = max.max(value);
+                sum += value;
+                count += 1;
+            }
+            let sparkline = points
+                .iter()
+                .filter(|&&[timestamp, _]| timestamp >= end - SPARKLINE)
+                .map(|&[timestamp, value]| [timestamp as f64, value])
+                .collect();
+            value.cards.insert(
+                (identifier, column),
+                Card {
+                    timestamp,
+                    value: last,
+                    statistics: (count > 0).then(|| Statistics {
+                        min,
+                        max,
+                        mean: sum / count as f64,
+                    }),
+                    sparkline,
+                },
+            );
+        }
+        Ok(value)
+    }
+}
+
+impl ComputerMut<Key<'_>, Value> for Computer {
+    fn compute(&mut self, key: Key) -> Value {
+        self.try_compute(key).unwrap_or_default()
+    }
+}
+
+/// Key
+#[derive(Clone, Copy, Debug, Hash)]
+pub(in crate::app) struct Key<'a> {
+    pub(crate) frame: &'a Hashed<MetaDataFrame>,
+    pub(crate) settings: &'a Settings,
+    /// Current time of the live pane, milliseconds rounded to seconds
+    pub(crate) now: Option<i64>,
+}
+
+/// Value
+#[derive(Clone, Debug, Default)]
+pub(in crate::app) struct Value {
+    /// By identifier and value column
+    pub(in crate::app) cards: BTreeMap<(u64, String), Card>,
+}
+
+/// Latest value of a device column
+#[derive(Clone, Debug)]
+pub(in crate::app) struct Card {
+    /// Milliseconds
+    pub(in crate::app) timestamp: i64,
+    pub(in crate::app) value: f64,
+    /// Over the window, none if it is empty
+    pub(in crate::app) statistics: Option<Statistics>,
+    /// Last hour, milliseconds
+    pub(in crate::app) sparkline: Vec<[f64; 2]>,
+}
+
+/// Statistics over the window
+#[derive(Clone, Copy, Debug)]
+pub(in crate::app) struct Statistics {
+    pub(in crate::app) min: f64,
+    pub(in crate::app) max: f64,
+    pub(in crate::app) mean: f64,
+}
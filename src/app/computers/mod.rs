@@ -1,7 +1,9 @@
 pub(in crate::app) use self::{
+    dashboard::{Computed as DashboardComputed, Key as DashboardKey},
     plot::{Computed as PlotComputed, Key as PlotKey},
     table::{Computed as TableComputed, Key as TableKey},
 };
 
+mod dashboard;
 mod plot;
 mod table;
@@ -27,21 +27,36 @@ impl Computer {
         let mut value = Value::default();
         let mut lazy_frame = key.frame.data.clone().lazy();
         lazy_frame = lazy_frame.sort([IDENTIFIER, TIMESTAMP], Default::default());
-        // Source
-        value.source = source(lazy_frame.clone())?;
-        // Resampling
-        if key.settings.plot.resampling.mean {
-            value.resampling.mean = resampling_mean(lazy_frame.clone(), key)?;
-        }
-        if key.settings.plot.resampling.median {
-            value.resampling.median = resampling_median(lazy_frame.clone(), key)?;
-        }
-        // Rolling
-        if key.settings.plot.rolling.mean {
-            value.rolling.mean = rolling_mean(lazy_frame.clone(), key)?;
-        }
-        if key.settings.plot.rolling.median {
-            value.rolling.median = rolling_median(lazy_frame, key)?;
+        // One series per value column
+        for name in key.settings.values.selected(&key.frame.data) {
+            // Source
+            value.source.extend(source(lazy_frame.clone(), &name)?);
+            // Resampling
+            if key.settings.plot.resampling.mean {
+                value
+                    .resampling
+                    .mean
+                    .extend(resampling_mean(lazy_frame.clone(), &name, key)?);
+            }
+            if key.settings.plot.resampling.median {
+                value
+                    .resampling
+                    .median
+                    .extend(resampling_median(lazy_frame.clone(), &name, key)?);
+            }
+            // Rolling
+            if key.settings.plot.rolling.mean {
+                value
+                    .rolling
+                    .mean
+                    .extend(rolling_mean(lazy_frame.clone(), &name, key)?);
+            }
+            if key.settings.plot.rolling.median {
+                value
+                    .rolling
+                    .median
+                    .extend(rolling_median(lazy_frame.clone(), &name, key)?);
+            }
         }
         Ok(value)
     }
@@ -60,40 +75,45 @@ pub(in crate::app) struct Key<'a> {
     pub(crate) settings: &'a Settings,
 }
 
+/// Points by identifier and value column
+pub(in crate::app) type Points = BTreeMap<(u64, String), Vec<[f64; 2]>>;
+
 /// Value
 #[derive(Clone, Debug, Default)]
 pub(in crate::app) struct Value {
-    pub(in crate::app) source: BTreeMap<u64, Vec<[f64; 2]>>,
+    pub(in crate::app) source: Points,
     pub(in crate::app) resampling: Resampling,
     pub(in crate::app) rolling: Rolling,
 }
 
 #[derive(Clone, Debug, Default)]
 pub(in crate::app) struct Resampling {
-    pub(in crate::app) mean: BTreeMap<u64, Vec<[f64; 2]>>,
-    pub(in crate::app) median: BTreeMap<u64, Vec<[f64; 2]>>,
+    pub(in crate::app) mean: Points,
+    pub(in crate::app) median: Points,
 }
 
 #[derive(Clone, Debug, Default)]
 pub(in crate::app) struct Rolling {
-    pub(in crate::app) mean: BTreeMap<u64, Vec<[f64; 2]>>,
-    pub(in crate::app) median: BTreeMap<u64, Vec<[f64; 2]>>,
+    pub(in crate::app) mean: Points,
+    pub(in crate::app) median: Points,
 }
 
-fn source(lazy_frame: LazyFrame) -> PolarsResult<BTreeMap<u64, Vec<[f64; 2]>>> {
+fn source(lazy_frame: LazyFrame, name: &str) -> PolarsResult<Points> {
     collect(
+        name,
         lazy_frame.group_by([col(IDENTIFIER)]).agg([as_struct(vec![
             col(TIMESTAMP).alias(X),
-            last().alias(Y),
+            col(name).alias(Y),
         ])
         .alias(POINTS)]),
     )
 }
 
-fn resampling_mean(lazy_frame: LazyFrame, key: Key) -> PolarsResult<BTreeMap<u64, Vec<[f64; 2]>>> {
+fn resampling_mean(lazy_frame: LazyFrame, name: &str, key: Key) -> PolarsResult<Points> {
     let every = Duration::parse(&format!("{}s", key.settings.plot.resampling.every));
     let period = Duration::parse(&format!("{}s", key.settings.plot.resampling.period));
     collect(
+        name,
         lazy_frame
             .group_by_dynamic(
                 col(TIMESTAMP),
@@ -105,19 +125,17 @@ fn resampling_mean(lazy_frame: LazyFrame, key: Key) -> PolarsResult<BTreeMap<u64
                     ..Default::default()
                 },
             )
-            .agg([last().mean()])
+            .agg([col(name).mean()])
             .group_by([col(IDENTIFIER)])
-            .agg([as_struct(vec![col(TIMESTAMP).alias(X), last().alias(Y)]).alias(POINTS)]),
+            .agg([as_struct(vec![col(TIMESTAMP).alias(X), col(name).alias(Y)]).alias(POINTS)]),
     )
 }
 
-fn resampling_median(
-    lazy_frame: LazyFrame,
-    key: Key,
-) -> PolarsResult<BTreeMap<u64, Vec<[f64; 2]>>> {
+fn resampling_median(lazy_frame: LazyFrame, name: &str, key: Key) -> PolarsResult<Points> {
     let every = Duration::parse(&format!("{}s", key.settings.plot.resampling.every));
     let period = Duration::parse(&format!("{}s", key.settings.plot.resampling.period));
     collect(
+        name,
         lazy_frame
             .group_by_dynamic(
                 col(TIMESTAMP),
@@ -129,17 +147,18 @@ fn resampling_median(
                     ..Default::default()
                 },
             )
-            .agg([last().median()])
+            .agg([col(name).median()])
             .group_by([col(IDENTIFIER)])
-            .agg([as_struct(vec![col(TIMESTAMP).alias(X), last().alias(Y)]).alias(POINTS)]),
+            .agg([as_struct(vec![col(TIMESTAMP).alias(X), col(name).alias(Y)]).alias(POINTS)]),
     )
 }
 
-fn rolling_mean(lazy_frame: LazyFrame, key: Key) -> PolarsResult<BTreeMap<u64, Vec<[f64; 2]>>> {
+fn rolling_mean(lazy_frame: LazyFrame, name: &str, key: Key) -> PolarsResult<Points> {
     collect(
+        name,
         lazy_frame.group_by([col(IDENTIFIER)]).agg([as_struct(vec![
             col(TIMESTAMP).alias(X),
-            last()
+            col(name)
                 .rolling_mean(RollingOptionsFixedWindow {
                     window_size: key.settings.plot.rolling.window_size,
                     min_periods: key.settings.plot.rolling.min_periods,
@@ -152,11 +171,12 @@ fn rolling_mean(lazy_frame: LazyFrame, key: Key) -> PolarsResult<BTreeMap<u64, V
     )
 }
 
-fn rolling_median(lazy_frame: LazyFrame, key: Key) -> PolarsResult<BTreeMap<u64, Vec<[f64; 2]>>> {
+fn rolling_median(lazy_frame: LazyFrame, name: &str, key: Key) -> PolarsResult<Points> {
     collect(
+        name,
         lazy_frame.group_by([col(IDENTIFIER)]).agg([as_struct(vec![
             col(TIMESTAMP).alias(X),
-            last()
+            col(name)
                 .rolling_median(RollingOptionsFixedWindow {
                     window_size: key.settings.plot.rolling.window_size,
                     min_periods: key.settings.plot.rolling.min_periods,
@@ -169,7 +189,7 @@ fn rolling_median(lazy_frame: LazyFrame, key: Key) -> PolarsResult<BTreeMap<u64,
     )
 }
 
-fn collect(lazy_frame: LazyFrame) -> PolarsResult<BTreeMap<u64, Vec<[f64; 2]>>> {
+fn collect(name: &str, lazy_frame: LazyFrame) -> PolarsResult<Points> {
     let data_frame = lazy_frame.collect()?;
     let mut value = BTreeMap::new();
     for (identifier, points) in zip(
@@ -179,7 +199,7 @@ fn collect(lazy_frame: LazyFrame) -> PolarsResult<BTreeMap<u64, Vec<[f64; 2]>>>
         let x = points.struct_()?.field_by_name(X)?;
         let y = points.struct_()?.field_by_name(Y)?;
         value.insert(
-            identifier,
+            (identifier, name.to_owned()),
             zip(
                 x.cast(&DataType::Float64)?.f64()?.into_no_null_iter(),
                 y.cast(&DataType::Float64)?.f64()?.into_no_null_iter(),
@@ -34,9 +34,20 @@ impl Computer {
         lazy_frame = match key.settings.table.sort {
             Sort::Identifier => lazy_frame.sort_by_exprs([col("Identifier")], sort_options),
             Sort::Timestamp => lazy_frame.sort_by_exprs([col("Timestamp")], sort_options),
-            Sort::Value => lazy_frame.sort_by_exprs([last()], sort_options),
+            Sort::Value => match key.settings.values.selected(&key.frame.data).first() {
+                Some(name) => lazy_frame.sort_by_exprs([col(name.clone())], sort_options),
+                None => lazy_frame.sort_by_exprs([col("Timestamp")], sort_options),
+            },
         };
-        lazy_frame.collect()
+        // Select
+        let columns = key
+            .settings
+            .table
+            .columns
+            .visible(&key.frame.data, &key.settings.values);
+        lazy_frame
+            .select(columns.into_iter().map(col).collect::<Vec<_>>())
+            .collect()
     }
 }
 
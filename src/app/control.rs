@@ -0,0 +1,537 @@
+use super::{
+    YMDHMS,
+    mqtt::{self, Connection, Settings, State, TOPIC_ATUC, TOPIC_AUDIT, TOPIC_COMMAND, TOPIC_DTEC},
+    panes::{Ddoc, Kind},
+};
+use anyhow::Result;
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use egui::{
+    Button, ComboBox, Context, DragValue, Grid, Id, Modal, RichText, ScrollArea, Ui, Window,
+};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::{
+    CHART_LINE_UP, CHECK, CLOCK_COUNTDOWN, HOURGLASS, SLIDERS_HORIZONTAL, WARNING, X,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+const TOPIC_DDOC: &str = "ddoc";
+const MAX_ENTRIES: usize = 1000;
+const ACK_TIMEOUT: TimeDelta = TimeDelta::seconds(30);
+
+/// Control
+///
+/// Publishes setpoints and commands to the device controllers on their command
+/// topics and keeps the audit log of the sent commands with their
+/// acknowledgements.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct Control {
+    /// Operator name written to the commands, the client identifier if empty
+    pub(crate) operator: String,
+    pub(crate) command: Command,
+    pub(crate) log: Vec<Entry>,
+    #[serde(skip)]
+    pub(crate) open: bool,
+    /// Command waiting for the confirmation
+    #[serde(skip)]
+    confirmation: Option<Command>,
+}
+
+impl Control {
+    /// Applies the received acknowledgements and expires the pending commands
+    pub(crate) fn update(&mut self, context: &Context) {
+        let acks = std::mem::take(&mut *mqtt::acks(context).lock());
+        for ack in acks {
+            match serde_json::from_slice::<Ack>(&ack) {
+                Ok(ack) => self.acknowledge(ack),
+                Err(error) => warn!(%error, "Invalid acknowledgement"),
+            }
+        }
+        let now = Utc::now();
+        for entry in &mut self.log {
+            if entry.state == Status::Pending && now - entry.time > ACK_TIMEOUT {
+                entry.state = Status::Timeout;
+            }
+        }
+    }
+
+    fn acknowledge(&mut self, ack: Ack) {
+        let Some(entry) = self.log.iter_mut().rev().find(|entry| entry.id == ack.id) else {
+            warn!(id = ack.id, "Acknowledgement of an unknown command");
+            return;
+        };
+        info!(id = ack.id, ok = ack.ok, "Acknowledged");
+        entry.state = if ack.ok {
+            Status::Acknowledged
+        } else {
+            Status::Rejected(ack.message.unwrap_or_default())
+        };
+    }
+
+    /// Publishes the command and logs it, the recipe names the runner. The
+    /// failed commands are logged too.
+    pub(crate) fn send(
+        &mut self,
+        context: &Context,
+        connection: &mut Connection,
+        settings: &Settings,
+        command: Command,
+        recipe: Option<&str>,
+    ) -> Result<()> {
+        let time = Utc::now();
+        // Unique and increasing within the log
+        let id =
+            (time.timestamp_micros() as u64).max(self.log.last().map_or(0, |entry| entry.id + 1));
+        let sender = if self.operator.is_empty() {
+            settings.client_id()
+        } else {
+            self.operator.clone()
+        };
+        let mut entry = Entry {
+            id,
+            time,
+            sender,
+            device: command.device,
+            identifier: command.identifier,
+            action: command.action,
+            value: command.action.has_value().then_some(command.value),
+            recipe: recipe.map(ToOwned::to_owned),
+            state: Status::Pending,
+        };
+        let result = publish(context, connection, &entry);
+        if let Err(error) = &result {
+            error!(%error);
+            entry.state = Status::Failed(error.to_string());
+        }
+        info!(?entry, "Command");
+        self.log.push(entry);
+        if self.log.len() > MAX_ENTRIES {
+            self.log.drain(..self.log.len() - MAX_ENTRIES);
+        }
+        result
+    }
+}
+
+impl Control {
+    pub(crate) fn window(
+        &mut self,
+        context: &Context,
+        connection: &mut Connection,
+        settings: &Settings,
+    ) {
+        let mut open = self.open;
+        Window::new(format!("{SLIDERS_HORIZONTAL} Control"))
+            .open(&mut open)
+            .show(context, |ui| self.show(ui, connection));
+        self.open = open;
+        // Confirmation
+        let Some(command) = self.confirmation.clone() else {
+            return;
+        };
+        let mut confirmed = None;
+        Modal::new(Id::new("ControlConfirmation")).show(context, |ui| {
+            ui.heading(ui.localize("control__confirm"));
+            command.show(ui);
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .button(format!("{CHECK} {}", ui.localize("control__send")))
+                    .clicked()
+                {
+                    confirmed = Some(true);
+                }
+                if ui
+                    .button(format!("{X} {}", ui.localize("control__cancel")))
+                    .clicked()
+                {
+                    confirmed = Some(false);
+                }
+            });
+        });
+        if let Some(confirmed) = confirmed {
+            self.confirmation = None;
+            if confirmed {
+                // Failures are shown in the log
+                self.send(context, connection, settings, command, None).ok();
+            }
+        }
+    }
+
+    fn show(&mut self, ui: &mut Ui, connection: &Connection) {
+        // Device
+        ui.horizontal(|ui| {
+            for device in [Device::Dtec, Device::Atuc, Device::Ddoc] {
+                ui.selectable_value(&mut self.command.device, device, ui.localize(device.text()))
+                    .on_hover_localized(device.hover_text());
+            }
+        });
+        if !self.command.device.actions().contains(&self.command.action) {
+            self.command.action = self.command.device.actions()[0];
+        }
+        ui.separator();
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            // Operator
+            ui.label(ui.localize("control__operator"))
+                .on_hover_localized("control__operator.hover");
+            ui.text_edit_singleline(&mut self.operator);
+            ui.end_row();
+            // Identifier
+            ui.label(ui.localize("control__identifier"))
+                .on_hover_localized("control__identifier.hover");
+            ui.add(DragValue::new(&mut self.command.identifier).hexadecimal(1, false, false));
+            ui.end_row();
+            // Command
+            ui.label(ui.localize("control__command"))
+                .on_hover_localized("control__command.hover");
+            ComboBox::from_id_salt("Command")
+                .selected_text(ui.localize(self.command.action.text()))
+                .show_ui(ui, |ui| {
+                    for &action in self.command.device.actions() {
+                        ui.selectable_value(
+                            &mut self.command.action,
+                            action,
+                            ui.localize(action.text()),
+                        )
+                        .on_hover_localized(action.hover_text());
+                    }
+                })
+                .response
+                .on_hover_localized(self.command.action.hover_text());
+            ui.end_row();
+            // Value
+            if self.command.action.has_value() {
+                ui.label(ui.localize("control__value"));
+                ui.add(
+                    DragValue::new(&mut self.command.value)
+                        .speed(0.1)
+                        .suffix(self.command.action.unit()),
+                );
+                ui.end_row();
+            }
+        });
+        let connected = connection.status().state == State::Connected;
+        if ui
+            .add_enabled(
+                connected,
+                Button::new(format!(
+                    "{SLIDERS_HORIZONTAL} {}",
+                    ui.localize("control__send")
+                )),
+            )
+            .on_hover_localized("control__send.hover")
+            .on_disabled_hover_localized("control__disconnected")
+            .clicked()
+        {
+            self.confirmation = Some(self.command.clone());
+        }
+        ui.separator();
+        // Audit log
+        ui.label(ui.localize("control__log"))
+            .on_hover_localized("control__log.hover");
+        ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+            Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+                for entry in self
+                    .log
+                    .iter()
+                    .rev()
+                    .filter(|entry| entry.device == self.command.device)
+                {
+                    entry.show(ui);
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
+
+/// Publishes the command and retains it as the last audited command of the
+/// device
+fn publish(context: &Context, connection: &mut Connection, entry: &Entry) -> Result<()> {
+    let payload = serde_json::to_vec(&Message::from(entry))?;
+    let device = entry.device.topic();
+    connection.publish(
+        context,
+        &format!("{device}/{TOPIC_COMMAND}"),
+        false,
+        payload.clone(),
+    )?;
+    connection.publish(context, &format!("{device}/{TOPIC_AUDIT}"), true, payload)
+}
+
+/// Controlled device
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Device {
+    #[default]
+    Dtec,
+    Atuc,
+    Ddoc,
+}
+
+impl Device {
+    pub(crate) fn topic(self) -> &'static str {
+        match self {
+            Self::Dtec => TOPIC_DTEC,
+            Self::Atuc => TOPIC_ATUC,
+            Self::Ddoc => TOPIC_DDOC,
+        }
+    }
+
+    pub(crate) fn actions(self) -> &'static [Action] {
+        match self {
+            Self::Dtec => &[Action::Setpoint],
+            Self::Atuc => &[Action::Dilution],
+            Self::Ddoc => &[
+                Action::Aeration,
+                Action::CalibrateZero,
+                Action::CalibrateSpan,
+            ],
+        }
+    }
+
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::Dtec => "digital_temperature_controller.abbreviation",
+            Self::Atuc => "analog_turbidity_controller.abbreviation",
+            Self::Ddoc => "digital_disolved_oxygen_controller.abbreviation",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::Dtec => "digital_temperature_controller.hover",
+            Self::Atuc => "analog_turbidity_controller.hover",
+            Self::Ddoc => "digital_disolved_oxygen_controller.hover",
+        }
+    }
+}
+
+/// Controller command
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Action {
+    /// Temperature setpoint
+    #[default]
+    Setpoint,
+    /// Dilution trigger
+    Dilution,
+    /// Oxygen saturation setpoint
+    Aeration,
+    /// Zero point calibration
+    CalibrateZero,
+    /// Span calibration at the given value
+    CalibrateSpan,
+}
+
+impl Action {
+    pub(crate) fn device(self) -> Device {
+        match self {
+            Self::Setpoint => Device::Dtec,
+            Self::Dilution => Device::Atuc,
+            Self::Aeration | Self::CalibrateZero | Self::CalibrateSpan => Device::Ddoc,
+        }
+    }
+
+    /// Pane kind showing the controlled value
+    pub(crate) fn kind(self) -> Kind {
+        match self {
+            Self::Setpoint => Kind::Dtec,
+            Self::Dilution => Kind::Atuc,
+            Self::Aeration | Self::CalibrateZero | Self::CalibrateSpan => Kind::Ddoc(Ddoc::V2),
+        }
+    }
+
+    pub(crate) fn has_value(self) -> bool {
+        matches!(self, Self::Setpoint | Self::Aeration | Self::CalibrateSpan)
+    }
+
+    pub(crate) fn unit(self) -> &'static str {
+        match self {
+            Self::Setpoint => " °C",
+            Self::Aeration | Self::CalibrateSpan => " %",
+            Self::Dilution | Self::CalibrateZero => "",
+        }
+    }
+
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::Setpoint => "command__setpoint",
+            Self::Dilution => "command__dilution",
+            Self::Aeration => "command__aeration",
+            Self::CalibrateZero => "command__calibrate_zero",
+            Self::CalibrateSpan => "command__calibrate_span",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::Setpoint => "command__setpoint.hover",
+            Self::Dilution => "command__dilution.hover",
+            Self::Aeration => "command__aeration.hover",
+            Self::CalibrateZero => "command__calibrate_zero.hover",
+            Self::CalibrateSpan => "command__calibrate_span.hover",
+        }
+    }
+}
+
+/// Command draft
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct Command {
+    pub(crate) device: Device,
+    pub(crate) identifier: u64,
+    pub(crate) action: Action,
+    pub(crate) value: f64,
+}
+
+impl Command {
+    pub(crate) fn new() -> Self {
+        Self {
+            device: Device::Dtec,
+            identifier: 1,
+            action: Action::Setpoint,
+            value: 25.0,
+        }
+    }
+}
+
+impl Command {
+    fn show(&self, ui: &mut Ui) {
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            ui.label(ui.localize("control__device"));
+            ui.label(ui.localize(self.device.text()));
+            ui.end_row();
+            ui.label(ui.localize("control__identifier"));
+            ui.label(format!("{:x}", self.identifier));
+            ui.end_row();
+            ui.label(ui.localize("control__command"));
+            ui.label(ui.localize(self.action.text()));
+            ui.end_row();
+            if self.action.has_value() {
+                ui.label(ui.localize("control__value"));
+                ui.label(format!("{}{}", self.value, self.action.unit()));
+                ui.end_row();
+            }
+        });
+    }
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Audit log entry
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct Entry {
+    pub(crate) id: u64,
+    pub(crate) time: DateTime<Utc>,
+    pub(crate) sender: String,
+    pub(crate) device: Device,
+    pub(crate) identifier: u64,
+    pub(crate) action: Action,
+    pub(crate) value: Option<f64>,
+    /// Recipe which published the command
+    #[serde(default)]
+    pub(crate) recipe: Option<String>,
+    pub(crate) state: Status,
+}
+
+impl Entry {
+    fn show(&self, ui: &mut Ui) {
+        ui.label(self.time.with_timezone(&Local).format(YMDHMS).to_string());
+        match &self.recipe {
+            Some(recipe) => ui.label(format!("{} {CHART_LINE_UP} {recipe}", self.sender)),
+            None => ui.label(&self.sender),
+        };
+        ui.label(format!("{:x}", self.identifier));
+        ui.label(ui.localize(self.action.text()));
+        if let Some(value) = self.value {
+            ui.label(format!("{value}{}", self.action.unit()));
+        } else {
+            ui.label("-");
+        }
+        let response = match &self.state {
+            Status::Pending => ui.label(HOURGLASS),
+            Status::Acknowledged => {
+                ui.label(RichText::new(CHECK).color(ui.visuals().hyperlink_color))
+            }
+            Status::Rejected(_) | Status::Failed(_) => {
+                ui.label(RichText::new(X).color(ui.visuals().error_fg_color))
+            }
+            Status::Timeout => {
+                ui.label(RichText::new(CLOCK_COUNTDOWN).color(ui.visuals().warn_fg_color))
+            }
+        };
+        response.on_hover_ui(|ui| {
+            ui.label(ui.localize(self.state.text()));
+            if let Status::Rejected(message) | Status::Failed(message) = &self.state {
+                if !message.is_empty() {
+                    ui.label(format!("{WARNING} {message}"));
+                }
+            }
+        });
+    }
+}
+
+/// Acknowledgement status of the command
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) enum Status {
+    Pending,
+    Acknowledged,
+    Rejected(String),
+    Timeout,
+    /// Not published
+    Failed(String),
+}
+
+impl Status {
+    pub(crate) fn text(&self) -> &'static str {
+        match self {
+            Self::Pending => "ack__pending",
+            Self::Acknowledged => "ack__acknowledged",
+            Self::Rejected(_) => "ack__rejected",
+            Self::Timeout => "ack__timeout",
+            Self::Failed(_) => "ack__failed",
+        }
+    }
+}
+
+/// Published command
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct Message<'a> {
+    id: u64,
+    identifier: u64,
+    command: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<f64>,
+    sender: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recipe: Option<&'a str>,
+    /// Milliseconds since the epoch
+    timestamp: i64,
+}
+
+impl<'a> From<&'a Entry> for Message<'a> {
+    fn from(entry: &'a Entry) -> Self {
+        Self {
+            id: entry.id,
+            identifier: entry.identifier,
+            command: entry.action,
+            value: entry.value,
+            sender: &entry.sender,
+            recipe: entry.recipe.as_deref(),
+            timestamp: entry.time.timestamp_millis(),
+        }
+    }
+}
+
+/// Received acknowledgement
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Ack {
+    id: u64,
+    ok: bool,
+    #[serde(default)]
+    message: Option<String>,
+}
@@ -1,31 +1,52 @@
 use super::{
     YMDHMS,
-    metadata::{FILE, ICON, MAX_TIMESTAMP, MIN_TIMESTAMP, NAME},
+    loader::Loader,
+    metadata::{FILE, ICON, KIND, MAX_TIMESTAMP, MIN_TIMESTAMP, Metadata, NAME},
     panes::Kind,
+    replay::Replay,
 };
 use crate::{
-    app::{metadata::MetaDataFrame, panes::Pane},
+    app::{
+        metadata::{MetaDataFrame, MetadataWidget},
+        panes::Pane,
+    },
     utils::hashed::Hashed,
 };
-use anyhow::Result;
-use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
-use egui::{CentralPanel, Grid, Label, RichText, ScrollArea, Sense, TopBottomPanel, Ui, menu::bar};
+use anyhow::{Result, bail};
+use arrow::temporal_conversions::timestamp_ms_to_datetime;
+use chrono::{DateTime, FixedOffset, Utc};
+use egui::{
+    Button, CentralPanel, ComboBox, Grid, Label, RichText, ScrollArea, Sense, TopBottomPanel, Ui,
+    menu::bar,
+};
 use egui_extras::{Column, TableBuilder};
 use egui_l20n::{ResponseExt, UiExt as _};
-use egui_phosphor::regular::{BROWSERS, CHECK, MINUS, TRASH};
+use egui_phosphor::regular::{BROWSERS, CHECK, COPY, GIT_MERGE, MINUS, PLAY, TRASH, WARNING};
 use egui_tiles::Tree;
 use egui_tiles_ext::{TreeExt, VERTICAL};
 use indexmap::IndexSet;
-use polars::frame::DataFrame;
+use polars::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashSet, hash_map::Entry};
-use tracing::instrument;
+use std::collections::{HashSet, hash_map::Entry};
+use tracing::{info, instrument};
+
+const IDENTIFIER: &str = "Identifier";
+const TIMESTAMP: &str = "Timestamp";
+const COUNT: &str = "Count";
 
 /// Data
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub(crate) struct Data {
     pub(crate) frames: IndexSet<MetaDataFrame>,
     pub(crate) selected: HashSet<MetaDataFrame>,
+    #[serde(default)]
+    pub(crate) resolution: Resolution,
+    /// Last merge of the selected frames
+    #[serde(skip)]
+    report: Option<Report>,
+    /// Last browse, merge or replay error
+    #[serde(skip)]
+    error: Option<String>,
 }
 
 impl Data {
@@ -39,31 +60,65 @@ impl Data {
         frame.data.rechunk_mut();
         self.frames.insert(frame);
         self.frames.sort_by(|left, right| {
-            left.meta[NAME]
-                .cmp(&right.meta[NAME])
-                .then(left.meta[MIN_TIMESTAMP].cmp(&right.meta[MIN_TIMESTAMP]))
-                .then(left.meta[MAX_TIMESTAMP].cmp(&right.meta[MAX_TIMESTAMP]))
+            // Frames without rows have no timestamp range
+            let key = |frame: &MetaDataFrame| {
+                (
+                    frame.meta.get(NAME).cloned(),
+                    frame.meta.get(MIN_TIMESTAMP).cloned(),
+                    frame.meta.get(MAX_TIMESTAMP).cloned(),
+                )
+            };
+            key(left).cmp(&key(right))
         });
     }
 }
 
 impl Data {
-    pub(crate) fn show(&mut self, ui: &mut Ui, tree: &mut Tree<Pane>) {
+    /// The selected frames are of a single kind and can be stacked
+    fn compatible(&self) -> bool {
+        let mut selected = self
+            .frames
+            .iter()
+            .filter(|frame| self.selected.contains(*frame));
+        let Some(first) = selected.next() else {
+            return false;
+        };
+        let (first_kind, first_name) = (kind(&first.meta), first.meta.get(NAME));
+        selected.all(|frame| {
+            // Generic frames stack only with the same value columns
+            kind(&frame.meta) == first_kind
+                && (first_kind != Kind::Generic || frame.meta.get(NAME) == first_name)
+        })
+    }
+
+    pub(crate) fn show(
+        &mut self,
+        ui: &mut Ui,
+        tree: &mut Tree<Pane>,
+        replay: &mut Replay,
+        loader: &mut Loader,
+    ) {
         // Header
         TopBottomPanel::top(ui.auto_id_with("TopPanel")).show_inside(ui, |ui| {
             bar(ui, |ui| {
                 ScrollArea::horizontal().show(ui, |ui| {
-                    self.header(ui, tree);
+                    self.header(ui, tree, replay);
                 })
             })
         });
+        // Loading files
+        if !loader.is_empty() {
+            TopBottomPanel::bottom(ui.auto_id_with("BottomPanel")).show_inside(ui, |ui| {
+                loader.show(ui);
+            });
+        }
         // Body
         CentralPanel::default().show_inside(ui, |ui| {
             self.body(ui);
         });
     }
 
-    fn header(&mut self, ui: &mut Ui, tree: &mut Tree<Pane>) {
+    fn header(&mut self, ui: &mut Ui, tree: &mut Tree<Pane>, replay: &mut Replay) {
         ui.heading(ui.localize("loaded_files"))
             .on_hover_localized("loaded_files.hover");
         ui.separator();
@@ -87,25 +142,102 @@ impl Data {
             .on_hover_localized("delete_all")
             .clicked()
         {
-            *self = Default::default();
+            *self = Self {
+                resolution: self.resolution,
+                ..Default::default()
+            };
         }
         ui.separator();
+        // Browse
+        let compatible = self.compatible();
         if ui
-            .button(RichText::new(BROWSERS).heading())
+            .add_enabled(compatible, Button::new(RichText::new(BROWSERS).heading()))
             .on_hover_localized("browse")
+            .on_disabled_hover_localized("browse__incompatible")
+            .clicked()
+        {
+            match reduce(self.selected(), self.resolution) {
+                Ok((frame, report)) => {
+                    self.report = Some(report);
+                    self.error = None;
+                    let kind = kind(&frame.meta);
+                    // Unknown values are browsed as a generic topic named after them
+                    let topic = match kind {
+                        Kind::Generic => frame.meta.get(NAME).cloned().unwrap_or_default(),
+                        _ => String::new(),
+                    };
+                    let pane = Pane {
+                        kind,
+                        frame: Hashed::new(frame),
+                        settings: Default::default(),
+                        state: Default::default(),
+                        view: Default::default(),
+                        real_time: false,
+                        topic,
+                        version: None,
+                    };
+                    tree.insert_pane::<VERTICAL>(pane);
+                }
+                Err(error) => {
+                    self.report = None;
+                    self.error = Some(error.to_string());
+                }
+            }
+        }
+        // Merge
+        if ui
+            .add_enabled(compatible, Button::new(RichText::new(GIT_MERGE).heading()))
+            .on_hover_localized("merge.hover")
+            .on_disabled_hover_localized("browse__incompatible")
             .clicked()
         {
-            if let Ok(frame) = reduce(self.selected()) {
-                let pane = Pane {
-                    kind: Kind::Dtec,
-                    frame: Hashed::new(frame),
-                    settings: Default::default(),
-                    state: Default::default(),
-                    view: Default::default(),
-                };
-                tree.insert_pane::<VERTICAL>(pane);
+            match reduce(self.selected(), self.resolution) {
+                Ok((frame, report)) => {
+                    self.report = Some(report);
+                    self.error = None;
+                    self.add(frame);
+                }
+                Err(error) => {
+                    self.report = None;
+                    self.error = Some(error.to_string());
+                }
             }
         }
+        ComboBox::from_id_salt("Resolution")
+            .selected_text(ui.localize(self.resolution.text()))
+            .show_ui(ui, |ui| {
+                for resolution in [Resolution::First, Resolution::Last, Resolution::Drop] {
+                    ui.selectable_value(
+                        &mut self.resolution,
+                        resolution,
+                        ui.localize(resolution.text()),
+                    )
+                    .on_hover_localized(resolution.hover_text());
+                }
+            })
+            .response
+            .on_hover_localized(self.resolution.hover_text());
+        if let Some(report) = &self.report {
+            report.show(ui);
+        }
+        // Replay
+        if ui
+            .add_enabled(
+                !self.selected.is_empty(),
+                Button::new(RichText::new(PLAY).heading()),
+            )
+            .on_hover_localized("replay.hover")
+            .clicked()
+        {
+            self.error = replay
+                .load(ui.ctx(), self.selected())
+                .err()
+                .map(|error| error.to_string());
+        }
+        if let Some(error) = &self.error {
+            ui.label(RichText::new(WARNING).color(ui.visuals().error_fg_color))
+                .on_hover_text(error);
+        }
         ui.separator();
     }
 
@@ -139,18 +271,23 @@ impl Data {
                     });
                     // Label
                     row.col(|ui| {
-                        let text = format!("{} {}", frame.meta[ICON], frame.meta[FILE],);
+                        let text = format!(
+                            "{} {}",
+                            frame.meta.get(ICON).map_or("", String::as_str),
+                            frame.meta.get(FILE).map_or("", String::as_str),
+                        );
                         if ui
                             .add(Label::new(text).sense(Sense::click()).truncate())
                             .on_hover_ui(|ui| {
-                                ui.label(format!(
-                                    "{} {MINUS} {}",
-                                    frame.meta[MIN_TIMESTAMP], frame.meta[MAX_TIMESTAMP],
-                                ));
+                                if let (Some(min), Some(max)) =
+                                    (frame.meta.get(MIN_TIMESTAMP), frame.meta.get(MAX_TIMESTAMP))
+                                {
+                                    ui.label(format!("{min} {MINUS} {max}"));
+                                }
+                            })
+                            .on_hover_ui(|ui| {
+                                MetadataWidget::new(&frame.meta).show(ui);
                             })
-                            // .on_hover_ui(|ui| {
-                            //     MetadataWidget::new(&frame.meta).show(ui);
-                            // })
                             .on_hover_ui(|ui| {
                                 Grid::new(ui.next_auto_id()).show(ui, |ui| {
                                     ui.label("Rows");
@@ -197,39 +334,175 @@ impl Data {
     }
 }
 
+/// Device kind by the value column name or the written kind metadata
+fn kind(meta: &Metadata) -> Kind {
+    [NAME, KIND]
+        .into_iter()
+        .filter_map(|key| meta.get(key))
+        .find_map(|name| Kind::from_name(name))
+        .unwrap_or(Kind::Generic)
+}
+
+/// Merges the frames ordered by the identifier and the timestamp
+///
+/// Exact duplicate rows are removed, the rows of the same identifier and
+/// timestamp with different values are resolved by the resolution.
 #[instrument(skip(frames), err)]
-fn reduce(frames: impl Iterator<Item = MetaDataFrame>) -> Result<MetaDataFrame> {
-    let mut meta = BTreeMap::new();
-    let mut min_timestamp = NaiveDateTime::MAX;
-    let mut max_timestamp = NaiveDateTime::MIN;
-    let mut data = DataFrame::empty();
+fn reduce(
+    frames: impl Iterator<Item = MetaDataFrame>,
+    resolution: Resolution,
+) -> Result<(MetaDataFrame, Report)> {
+    let mut meta = None::<Metadata>;
+    let mut files = Vec::new();
+    let mut lazy_frames = Vec::new();
     for frame in frames {
-        min_timestamp = min_timestamp.min(NaiveDateTime::parse_from_str(
-            &frame.meta[MIN_TIMESTAMP],
-            YMDHMS,
-        )?);
-        max_timestamp = max_timestamp.max(NaiveDateTime::parse_from_str(
-            &frame.meta[MAX_TIMESTAMP],
-            YMDHMS,
-        )?);
-        meta = frame.meta;
-        data = data.vstack(&frame.data)?;
+        files.extend(frame.meta.get(FILE).cloned());
+        // Keep the metadata all the frames agree on
+        match &mut meta {
+            Some(meta) => meta.retain(|key, value| frame.meta.get(key) == Some(&*value)),
+            None => meta = Some(frame.meta.clone()),
+        }
+        lazy_frames.push(frame.data.lazy());
     }
+    let Some(mut meta) = meta else {
+        bail!("No frames selected");
+    };
+    let stacked = concat(lazy_frames, UnionArgs::default())?.collect()?;
+    // Exact duplicates
+    let unique = stacked
+        .clone()
+        .lazy()
+        .unique_stable(None, UniqueKeepStrategy::First)
+        .collect()?;
+    let duplicates = stacked.height() - unique.height();
+    // Conflicts
+    let pairs = unique
+        .clone()
+        .lazy()
+        .group_by([col(IDENTIFIER), col(TIMESTAMP)])
+        .agg([len().alias(COUNT)])
+        .filter(col(COUNT).gt(lit(1)))
+        .select([
+            col(IDENTIFIER),
+            col(TIMESTAMP).dt().timestamp(TimeUnit::Milliseconds),
+        ])
+        .sort([IDENTIFIER, TIMESTAMP], Default::default())
+        .collect()?;
+    let conflicts = pairs
+        .column(IDENTIFIER)?
+        .u64()?
+        .iter()
+        .zip(pairs.column(TIMESTAMP)?.i64()?)
+        .filter_map(|(identifier, timestamp)| Some((identifier?, timestamp?)))
+        .collect();
+    let mut data = unique
+        .lazy()
+        .unique_stable(
+            Some(vec![IDENTIFIER.into(), TIMESTAMP.into()]),
+            resolution.strategy(),
+        )
+        .sort([IDENTIFIER, TIMESTAMP], Default::default())
+        .collect()?;
     data.rechunk_mut();
-    meta.remove(FILE);
-    meta.insert(
-        MIN_TIMESTAMP.to_owned(),
-        min_timestamp.format(YMDHMS).to_string(),
-    );
-    meta.insert(
-        MAX_TIMESTAMP.to_owned(),
-        max_timestamp.format(YMDHMS).to_string(),
+    // The derived metadata covers the merged data
+    meta.extend(super::meta(&data)?);
+    meta.insert(FILE.to_owned(), files.join(", "));
+    let report = Report {
+        duplicates,
+        conflicts,
+        resolution,
+    };
+    info!(
+        duplicates,
+        conflicts = report.conflicts.len(),
+        rows = data.height(),
+        "Merged"
     );
-    Ok(MetaDataFrame::new(meta, data))
+    Ok((MetaDataFrame::new(meta, data), report))
+}
+
+/// Resolution of the rows with the same identifier and timestamp but
+/// different values
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub(crate) enum Resolution {
+    /// Keep the row of the first frame
+    First,
+    /// Keep the row of the last frame
+    #[default]
+    Last,
+    /// Drop all the conflicting rows
+    Drop,
+}
+
+impl Resolution {
+    fn strategy(self) -> UniqueKeepStrategy {
+        match self {
+            Self::First => UniqueKeepStrategy::First,
+            Self::Last => UniqueKeepStrategy::Last,
+            Self::Drop => UniqueKeepStrategy::None,
+        }
+    }
+
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::First => "resolution__first",
+            Self::Last => "resolution__last",
+            Self::Drop => "resolution__drop",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::First => "resolution__first.hover",
+            Self::Last => "resolution__last.hover",
+            Self::Drop => "resolution__drop.hover",
+        }
+    }
+}
+
+/// Merge report
+#[derive(Clone, Debug)]
+struct Report {
+    /// Removed exact duplicate rows
+    duplicates: usize,
+    /// Identifier and timestamp (ms) pairs with different values
+    conflicts: Vec<(u64, i64)>,
+    resolution: Resolution,
+}
+
+impl Report {
+    fn show(&self, ui: &mut Ui) {
+        let conflicts = self.conflicts.len();
+        let text = format!("{COPY} {} {WARNING} {conflicts}", self.duplicates);
+        let response = if conflicts > 0 {
+            ui.label(RichText::new(text).color(ui.visuals().warn_fg_color))
+        } else {
+            ui.label(text)
+        };
+        response.on_hover_ui(|ui| {
+            ui.label(ui.localize(&format!("merge__duplicates?count={}", self.duplicates)));
+            ui.label(ui.localize(&format!("merge__conflicts?count={conflicts}")));
+            if conflicts > 0 {
+                ui.label(ui.localize(self.resolution.hover_text()));
+                ui.separator();
+                ScrollArea::vertical().max_height(256.0).show(ui, |ui| {
+                    Grid::new("MergeConflicts").striped(true).show(ui, |ui| {
+                        for &(identifier, timestamp) in &self.conflicts {
+                            ui.label(RichText::new(format!("{identifier:x}")).monospace());
+                            if let Some(timestamp) = timestamp_ms_to_datetime(timestamp) {
+                                ui.label(timestamp.format(YMDHMS).to_string());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            }
+        });
+    }
 }
 
 // impl Data {
-//     pub(crate) fn show(&mut self, ui: &mut Ui, tree: &mut Tree<Pane>) {
+//     pub(crate) fn show(&mut self, ui: &mut Ui, tree: &mut Tree<Pane>, replay: &mut Replay) {
 //         // Header
 //         bar(ui, |ui| {
 //             ui.heading(ui.localize("loaded_files"))
@@ -0,0 +1,904 @@
+use super::{
+    meta,
+    metadata::{FILE, MetaDataFrame},
+};
+use anyhow::{Context as _, Result, bail};
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone as _};
+use egui::{
+    Button, Checkbox, ComboBox, Context, DragValue, Grid, Id, RichText, ScrollArea, TextEdit, Ui,
+    Window,
+};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::FILE_CSV;
+use polars::prelude::*;
+use std::{collections::VecDeque, io::Cursor, mem::take};
+use tracing::{info, instrument};
+
+const IDENTIFIER: &str = "Identifier";
+const TIMESTAMP: &str = "Timestamp";
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+/// Lines used to detect the options and shown in the preview
+const SAMPLE_LINES: usize = 32;
+const PREVIEW_ROWS: usize = 8;
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+const MINUTES_PER_HOUR: i32 = 60;
+/// Offsets in use, minutes
+const MIN_OFFSET: i32 = -12 * MINUTES_PER_HOUR;
+const MAX_OFFSET: i32 = 14 * MINUTES_PER_HOUR;
+/// Offsets are multiples of a quarter of an hour
+const OFFSET_STEP: f64 = 15.0;
+/// Naive datetime formats tried in order
+const FORMATS: [&str; 9] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%d.%m.%Y %H:%M:%S%.f",
+    "%d.%m.%Y %H:%M",
+    "%d/%m/%Y %H:%M:%S%.f",
+    "%m/%d/%Y %H:%M:%S%.f",
+    "%Y/%m/%d %H:%M:%S%.f",
+    "%Y.%m.%d %H:%M:%S%.f",
+];
+
+/// CSV import
+///
+/// Dropped CSV and TSV files wait in the queue until their columns are mapped
+/// in the import window, the loader imports them then.
+#[derive(Debug, Default)]
+pub(crate) struct Import {
+    files: VecDeque<File>,
+}
+
+impl Import {
+    /// Queues the file for the mapping
+    pub(crate) fn open(&mut self, file: File) {
+        info!(name = file.name(), "CSV import");
+        self.files.push_back(file);
+    }
+
+    /// Shows the mapping of the first queued file, returns the mapped file
+    pub(crate) fn window(&mut self, context: &Context) -> Option<File> {
+        let queued = self.files.len();
+        let Some(file) = self.files.front_mut() else {
+            return None;
+        };
+        let mut open = true;
+        let mut action = None;
+        Window::new(format!("{FILE_CSV} {}", file.name))
+            .id(Id::new("Import"))
+            .open(&mut open)
+            .show(context, |ui| {
+                action = file.show(ui, queued);
+            });
+        match action {
+            Some(Action::Import) => self.files.pop_front(),
+            Some(Action::Skip) => {
+                self.files.pop_front();
+                None
+            }
+            None => {
+                // Closing skips only this file, the queued ones follow
+                if !open {
+                    self.files.pop_front();
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Whether the dropped file is imported as CSV
+pub(crate) fn is_csv(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.ends_with(".csv") || name.ends_with(".tsv") || name.ends_with(".txt")
+}
+
+/// Queued file
+#[derive(Debug)]
+pub(crate) struct File {
+    name: String,
+    bytes: Vec<u8>,
+    /// First lines
+    sample: Vec<String>,
+    options: Options,
+    mapping: Mapping,
+}
+
+impl File {
+    /// Detects the options and the mapping of the file
+    pub(crate) fn new(name: &str, mut bytes: Vec<u8>) -> Self {
+        if bytes.starts_with(BOM) {
+            bytes.drain(..BOM.len());
+        }
+        let sample = String::from_utf8_lossy(&bytes)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .take(SAMPLE_LINES)
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        let tab = name.to_lowercase().ends_with(".tsv");
+        let options = Options::detect(&sample, tab);
+        let mut file = Self {
+            name: name.to_owned(),
+            bytes,
+            sample,
+            options,
+            mapping: Mapping::default(),
+        };
+        file.mapping = file.detect_mapping();
+        file
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn columns(&self) -> Vec<String> {
+        let rows = self.options.rows(&self.sample);
+        if self.options.header {
+            if let Some(header) = rows.first() {
+                return header.clone();
+            }
+        }
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        // Names given by the reader to a headless file
+        (1..=width).map(|index| format!("column_{index}")).collect()
+    }
+
+    /// Rows without the header
+    fn records(&self) -> Vec<Vec<String>> {
+        let mut rows = self.options.rows(&self.sample);
+        if self.options.header && !rows.is_empty() {
+            rows.remove(0);
+        }
+        rows
+    }
+
+    fn detect_mapping(&self) -> Mapping {
+        let columns = self.columns();
+        let records = self.records();
+        let values = |index: usize| {
+            records
+                .iter()
+                .filter_map(move |record| record.get(index))
+                .filter(|value| !value.trim().is_empty())
+        };
+        let mut mapping = Mapping::default();
+        mapping.identifier = columns.iter().position(|column| {
+            let column = column.trim().to_lowercase();
+            column == "identifier" || column == "id"
+        });
+        mapping.timestamp = (0..columns.len())
+            .filter(|&index| Some(index) != mapping.identifier)
+            .find(|&index| {
+                let mut values = values(index).peekable();
+                values.peek().is_some()
+                    && values.all(|value| self.options.format.parse(value).is_some())
+            })
+            .or_else(|| (0..columns.len()).find(|&index| Some(index) != mapping.identifier))
+            .unwrap_or_default();
+        mapping.values = (0..columns.len())
+            .filter(|&index| Some(index) != mapping.identifier && index != mapping.timestamp)
+            .filter(|&index| {
+                let mut values = values(index).peekable();
+                values.peek().is_some()
+                    && values.all(|value| number(value, self.options.decimal_comma).is_some())
+            })
+            .map(|index| (index, columns[index].trim().to_owned()))
+            .collect();
+        mapping
+    }
+
+    #[instrument(skip(self), fields(name = %self.name), err)]
+    pub(crate) fn import(&self) -> Result<MetaDataFrame> {
+        if self.mapping.values.is_empty() {
+            bail!("No value columns");
+        }
+        let data = CsvReadOptions::default()
+            .with_has_header(self.options.header)
+            // Every column as string, the values are parsed with the options
+            .with_infer_schema_length(Some(0))
+            .with_parse_options(
+                CsvParseOptions::default()
+                    .with_separator(self.options.delimiter)
+                    .with_encoding(CsvEncoding::LossyUtf8),
+            )
+            .into_reader_with_file_handle(Cursor::new(self.bytes.as_slice()))
+            .finish()?;
+        if data.height() == 0 {
+            bail!("No rows");
+        }
+        // Identifier
+        let identifiers = match self.mapping.identifier {
+            Some(index) => column(&data, index)?
+                .iter()
+                .enumerate()
+                .map(|(row, value)| {
+                    let value = value.unwrap_or_default().trim();
+                    identifier(value)
+                        .with_context(|| format!("Row {}: invalid identifier {value:?}", row + 1))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![self.mapping.default_identifier; data.height()],
+        };
+        // Timestamp
+        let timestamps = column(&data, self.mapping.timestamp)?
+            .iter()
+            .enumerate()
+            .map(|(row, value)| {
+                let value = value.unwrap_or_default().trim();
+                self.options
+                    .format
+                    .parse(value)
+                    .and_then(|timestamp| timestamp.resolve(self.options.time_zone))
+                    .with_context(|| format!("Row {}: invalid timestamp {value:?}", row + 1))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let timestamps = Series::new(TIMESTAMP.into(), timestamps)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?;
+        // Values
+        let mut columns = vec![
+            Column::new(IDENTIFIER.into(), &identifiers),
+            timestamps.into_column(),
+        ];
+        for (index, name) in &self.mapping.values {
+            let values = column(&data, *index)?
+                .iter()
+                .map(|value| value.and_then(|value| number(value, self.options.decimal_comma)))
+                .collect::<Float64Chunked>()
+                .with_name(name.as_str().into());
+            columns.push(values.into_column());
+        }
+        let data = DataFrame::new(columns)?;
+        let mut meta = meta(&data)?;
+        meta.insert(FILE.to_owned(), self.name.clone());
+        Ok(MetaDataFrame::new(meta, data))
+    }
+}
+
+impl File {
+    fn show(&mut self, ui: &mut Ui, queued: usize) -> Option<Action> {
+        let options = self.options.clone();
+        // Options
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            // Delimiter
+            ui.label(ui.localize("import__delimiter"))
+                .on_hover_localized("import__delimiter.hover");
+            ComboBox::from_id_salt("ImportDelimiter")
+                .selected_text(ui.localize(delimiter(self.options.delimiter)))
+                .show_ui(ui, |ui| {
+                    for candidate in DELIMITERS {
+                        ui.selectable_value(
+                            &mut self.options.delimiter,
+                            candidate,
+                            ui.localize(delimiter(candidate)),
+                        );
+                    }
+                });
+            ui.end_row();
+            // Decimal comma
+            ui.label(ui.localize("import__decimal_comma"))
+                .on_hover_localized("import__decimal_comma.hover");
+            ui.add(Checkbox::without_text(&mut self.options.decimal_comma));
+            ui.end_row();
+            // Header
+            ui.label(ui.localize("import__header"))
+                .on_hover_localized("import__header.hover");
+            ui.add(Checkbox::without_text(&mut self.options.header));
+            ui.end_row();
+            // Format
+            ui.label(ui.localize("import__format"))
+                .on_hover_localized("import__format.hover");
+            ui.horizontal(|ui| {
+                ComboBox::from_id_salt("ImportFormat")
+                    .selected_text(ui.localize(self.options.format.text()))
+                    .show_ui(ui, |ui| {
+                        for format in [
+                            Format::Rfc3339,
+                            Format::Naive(FORMATS[0].to_owned()),
+                            Format::Seconds,
+                            Format::Milliseconds,
+                        ] {
+                            let selected = self.options.format.text() == format.text();
+                            if ui
+                                .selectable_label(selected, ui.localize(format.text()))
+                                .clicked()
+                                && !selected
+                            {
+                                self.options.format = format;
+                            }
+                        }
+                    });
+                if let Format::Naive(pattern) = &mut self.options.format {
+                    ComboBox::from_id_salt("ImportPattern")
+                        .selected_text(pattern.as_str())
+                        .show_ui(ui, |ui| {
+                            for candidate in FORMATS {
+                                ui.selectable_value(pattern, candidate.to_owned(), candidate);
+                            }
+                        });
+                    ui.add(TextEdit::singleline(pattern).desired_width(160.0));
+                }
+            });
+            ui.end_row();
+            // Time zone
+            ui.label(ui.localize("import__time_zone"))
+                .on_hover_localized("import__time_zone.hover");
+            ui.horizontal(|ui| {
+                let time_zone = &mut self.options.time_zone;
+                for candidate in [TimeZone::Utc, TimeZone::Local, TimeZone::Offset(0)] {
+                    let selected = time_zone.text() == candidate.text();
+                    if ui
+                        .selectable_label(selected, ui.localize(candidate.text()))
+                        .clicked()
+                        && !selected
+                    {
+                        *time_zone = candidate;
+                    }
+                }
+                if let TimeZone::Offset(minutes) = time_zone {
+                    ui.add(
+                        DragValue::new(minutes)
+                            .range(MIN_OFFSET..=MAX_OFFSET)
+                            .speed(OFFSET_STEP)
+                            .custom_formatter(|minutes, _| format_offset(minutes as _))
+                            .custom_parser(|text| parse_offset(text).map(Into::into)),
+                    );
+                }
+            });
+            ui.end_row();
+        });
+        if self.options.delimiter != options.delimiter || self.options.header != options.header {
+            self.mapping = self.detect_mapping();
+        }
+        ui.separator();
+        // Mapping
+        let columns = self.columns();
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            // Identifier
+            ui.label(ui.localize("import__identifier"))
+                .on_hover_localized("import__identifier.hover");
+            ui.horizontal(|ui| {
+                ComboBox::from_id_salt("ImportIdentifier")
+                    .selected_text(
+                        self.mapping
+                            .identifier
+                            .and_then(|index| columns.get(index).cloned())
+                            .unwrap_or_else(|| ui.localize("import__none")),
+                    )
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.mapping.identifier,
+                            None,
+                            ui.localize("import__none"),
+                        );
+                        for (index, column) in columns.iter().enumerate() {
+                            ui.selectable_value(&mut self.mapping.identifier, Some(index), column);
+                        }
+                    });
+                if self.mapping.identifier.is_none() {
+                    ui.add(
+                        DragValue::new(&mut self.mapping.default_identifier)
+                            .hexadecimal(1, false, false),
+                    );
+                }
+            });
+            ui.end_row();
+            // Timestamp
+            ui.label(ui.localize("import__timestamp"));
+            ComboBox::from_id_salt("ImportTimestamp")
+                .selected_text(
+                    columns
+                        .get(self.mapping.timestamp)
+                        .cloned()
+                        .unwrap_or_default(),
+                )
+                .show_ui(ui, |ui| {
+                    for (index, column) in columns.iter().enumerate() {
+                        ui.selectable_value(&mut self.mapping.timestamp, index, column);
+                    }
+                });
+            ui.end_row();
+            // Values
+            ui.label(ui.localize("import__values"))
+                .on_hover_localized("import__values.hover");
+            ui.vertical(|ui| {
+                for (index, column) in columns.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let position = self
+                            .mapping
+                            .values
+                            .iter()
+                            .position(|&(candidate, _)| candidate == index);
+                        let mut checked = position.is_some();
+                        if ui.checkbox(&mut checked, column).changed() {
+                            match position {
+                                Some(position) => {
+                                    self.mapping.values.remove(position);
+                                }
+                                None => self.mapping.values.push((index, column.trim().to_owned())),
+                            }
+                        }
+                        if let Some((_, name)) = self
+                            .mapping
+                            .values
+                            .iter_mut()
+                            .find(|(candidate, _)| *candidate == index)
+                        {
+                            ui.add(TextEdit::singleline(name).desired_width(120.0))
+                                .on_hover_localized("import__name.hover");
+                        }
+                    });
+                }
+            });
+            ui.end_row();
+        });
+        ui.separator();
+        // Preview
+        ui.label(ui.localize("import__preview"));
+        ScrollArea::both().max_height(160.0).show(ui, |ui| {
+            Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+                for column in &columns {
+                    ui.label(RichText::new(column).strong());
+                }
+                ui.end_row();
+                for record in self.records().iter().take(PREVIEW_ROWS) {
+                    for value in record {
+                        ui.label(value);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        ui.separator();
+        let mut action = None;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.mapping.values.is_empty(),
+                    Button::new(ui.localize("import__import")),
+                )
+                .clicked()
+            {
+                action = Some(Action::Import);
+            }
+            if ui
+                .button(ui.localize("import__skip"))
+                .on_hover_localized("import__skip.hover")
+                .clicked()
+            {
+                action = Some(Action::Skip);
+            }
+            if queued > 1 {
+                ui.label(ui.localize(&format!("import__queued?count={}", queued - 1)));
+            }
+        });
+        action
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Action {
+    Import,
+    Skip,
+}
+
+/// Read options
+#[derive(Clone, Debug, PartialEq)]
+struct Options {
+    delimiter: u8,
+    decimal_comma: bool,
+    header: bool,
+    format: Format,
+    /// Time zone of the naive timestamps
+    time_zone: TimeZone,
+}
+
+impl Options {
+    /// Detects the options from the first lines
+    fn detect(sample: &[String], tab: bool) -> Self {
+        let delimiter = if tab { b'\t' } else { detect_delimiter(sample) };
+        let rows = sample
+            .iter()
+            .map(|line| split(line, delimiter))
+            .collect::<Vec<_>>();
+        let format = detect_format(rows.iter().skip(1).flatten());
+        let parsed = |value: &String, decimal_comma| {
+            number(value, decimal_comma).is_some() || format.parse(value).is_some()
+        };
+        // Decimal comma is only possible when the comma isn't the delimiter
+        let decimal_comma = delimiter != b','
+            && rows.iter().skip(1).flatten().any(|value| {
+                value.contains(',')
+                    && number(value, true).is_some()
+                    && number(value, false).is_none()
+            });
+        // Header if a field of the first row isn't data while the second is
+        let header = match &rows[..] {
+            [first, second, ..] => first.iter().zip(second).any(|(first, second)| {
+                !parsed(first, decimal_comma) && parsed(second, decimal_comma)
+            }),
+            [first] => first.iter().any(|value| !parsed(value, decimal_comma)),
+            [] => true,
+        };
+        Self {
+            delimiter,
+            decimal_comma,
+            header,
+            format,
+            time_zone: TimeZone::Local,
+        }
+    }
+
+    fn rows(&self, sample: &[String]) -> Vec<Vec<String>> {
+        sample
+            .iter()
+            .map(|line| split(line, self.delimiter))
+            .collect()
+    }
+}
+
+/// Column mapping
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Mapping {
+    identifier: Option<usize>,
+    /// Identifier of the rows if there is no identifier column
+    default_identifier: u64,
+    timestamp: usize,
+    /// Value columns and their names
+    values: Vec<(usize, String)>,
+}
+
+/// Timestamp format
+#[derive(Clone, Debug, PartialEq)]
+enum Format {
+    /// With the offset, the time zone is ignored
+    Rfc3339,
+    /// Naive with the pattern
+    Naive(String),
+    /// Unix time, seconds
+    Seconds,
+    /// Unix time, milliseconds
+    Milliseconds,
+}
+
+impl Format {
+    fn text(&self) -> &'static str {
+        match self {
+            Self::Rfc3339 => "import__rfc3339",
+            Self::Naive(_) => "import__naive",
+            Self::Seconds => "import__seconds",
+            Self::Milliseconds => "import__milliseconds",
+        }
+    }
+
+    fn parse(&self, value: &str) -> Option<Timestamp> {
+        let value = value.trim();
+        match self {
+            Self::Rfc3339 => DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|date_time| Timestamp::Aware(date_time.timestamp_millis())),
+            Self::Naive(pattern) => NaiveDateTime::parse_from_str(value, pattern)
+                .ok()
+                .map(Timestamp::Naive),
+            Self::Seconds => value
+                .parse::<f64>()
+                .ok()
+                .map(|seconds| Timestamp::Aware((seconds * 1000.0).round() as _)),
+            Self::Milliseconds => value.parse().ok().map(Timestamp::Aware),
+        }
+    }
+}
+
+/// Parsed timestamp
+#[derive(Clone, Copy, Debug)]
+enum Timestamp {
+    /// Milliseconds
+    Aware(i64),
+    Naive(NaiveDateTime),
+}
+
+impl Timestamp {
+    /// Milliseconds
+    fn resolve(self, time_zone: TimeZone) -> Option<i64> {
+        let naive = match self {
+            Self::Aware(milliseconds) => return Some(milliseconds),
+            Self::Naive(naive) => naive,
+        };
+        match time_zone {
+            TimeZone::Utc => Some(naive.and_utc().timestamp_millis()),
+            TimeZone::Local => Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|date_time| date_time.timestamp_millis()),
+            TimeZone::Offset(minutes) => FixedOffset::east_opt(minutes * 60)?
+                .from_local_datetime(&naive)
+                .single()
+                .map(|date_time| date_time.timestamp_millis()),
+        }
+    }
+}
+
+/// Time zone of the naive timestamps
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TimeZone {
+    Utc,
+    Local,
+    /// Minutes east of UTC
+    Offset(i32),
+}
+
+impl TimeZone {
+    fn text(&self) -> &'static str {
+        match self {
+            Self::Utc => "time_zone__utc",
+            Self::Local => "time_zone__local",
+            Self::Offset(_) => "import__offset",
+        }
+    }
+}
+
+fn delimiter(delimiter: u8) -> &'static str {
+    match delimiter {
+        b',' => "import__comma",
+        b';' => "import__semicolon",
+        b'\t' => "import__tab",
+        _ => "import__pipe",
+    }
+}
+
+/// The delimiter with the same non zero count on every line, the most frequent
+/// one otherwise
+fn detect_delimiter(sample: &[String]) -> u8 {
+    let counts = |delimiter| {
+        sample
+            .iter()
+            .map(|line| split(line, delimiter).len() - 1)
+            .collect::<Vec<_>>()
+    };
+    let consistent = DELIMITERS
+        .into_iter()
+        .filter_map(|delimiter| {
+            let counts = counts(delimiter);
+            let first = *counts.first()?;
+            (first > 0 && counts.iter().all(|&count| count == first)).then_some((delimiter, first))
+        })
+        .max_by_key(|&(_, count)| count);
+    if let Some((delimiter, _)) = consistent {
+        return delimiter;
+    }
+    DELIMITERS
+        .into_iter()
+        .max_by_key(|&delimiter| counts(delimiter).iter().sum::<usize>())
+        .unwrap_or(b',')
+}
+
+/// The first format any of the values parse with
+fn detect_format<'a>(values: impl Iterator<Item = &'a String> + Clone) -> Format {
+    let parses = |format: &Format| values.clone().any(|value| format.parse(value).is_some());
+    if parses(&Format::Rfc3339) {
+        return Format::Rfc3339;
+    }
+    FORMATS
+        .into_iter()
+        .map(|pattern| Format::Naive(pattern.to_owned()))
+        .find(parses)
+        .unwrap_or_else(|| Format::Naive(FORMATS[0].to_owned()))
+}
+
+/// Splits the line by the delimiter outside of the quotes
+fn split(line: &str, delimiter: u8) -> Vec<String> {
+    let delimiter = delimiter as char;
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            char if char == delimiter && !quoted => fields.push(take(&mut field)),
+            char => field.push(char),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Offset as `+05:30`
+fn format_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+    format!(
+        "{sign}{:02}:{:02}",
+        minutes / MINUTES_PER_HOUR,
+        minutes % MINUTES_PER_HOUR,
+    )
+}
+
+/// Offset minutes from `+05:30`, `-3` or `5.75`
+fn parse_offset(text: &str) -> Option<i32> {
+    let text = text.trim();
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(text) => (-1, text),
+        None => (1, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let minutes = match text.split_once(':') {
+        Some((hours, minutes)) => {
+            let minutes = minutes.parse::<i32>().ok()?;
+            if !(0..MINUTES_PER_HOUR).contains(&minutes) {
+                return None;
+            }
+            hours.parse::<i32>().ok()? * MINUTES_PER_HOUR + minutes
+        }
+        None => (text.parse::<f64>().ok()? * MINUTES_PER_HOUR as f64).round() as i32,
+    };
+    Some(sign * minutes)
+}
+
+fn column(data: &DataFrame, index: usize) -> Result<&StringChunked> {
+    let Some(column) = data.get_columns().get(index) else {
+        bail!("Column {} is missing", index + 1);
+    };
+    Ok(column.str()?)
+}
+
+fn number(value: &str, decimal_comma: bool) -> Option<f64> {
+    let value = value.trim();
+    if decimal_comma {
+        // Spaces group the thousands in the Russian locale
+        let value = value
+            .chars()
+            .filter(|char| !char.is_whitespace())
+            .map(|char| if char == ',' { '.' } else { char })
+            .collect::<String>();
+        value.parse().ok()
+    } else {
+        value.parse().ok()
+    }
+}
+
+fn identifier(value: &str) -> Option<u64> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hexadecimal) => u64::from_str_radix(hexadecimal, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|&line| line.to_owned()).collect()
+    }
+
+    #[test]
+    fn split_quoted() {
+        assert_eq!(
+            split(r#"a,"b,c","d ""e""""#, b','),
+            lines(&["a", "b,c", r#"d "e""#]),
+        );
+        assert_eq!(split("1;2\r", b';'), lines(&["1", "2"]));
+        assert_eq!(split("a,", b','), lines(&["a", ""]));
+    }
+
+    #[test]
+    fn detect_delimiters() {
+        // Semicolon with the decimal comma
+        let sample = lines(&[
+            "Timestamp;Value",
+            "2024-01-01 00:00:00;1,5",
+            "2024-01-01 00:01:00;2,25",
+        ]);
+        assert_eq!(detect_delimiter(&sample), b';');
+        // Delimiters in the quoted fields
+        let sample = lines(&[r#"Timestamp,"Value, mg/L""#, r#"2024-01-01 00:00:00,"1,5""#]);
+        assert_eq!(detect_delimiter(&sample), b',');
+        assert_eq!(detect_delimiter(&lines(&["a\tb", "1\t2"])), b'\t');
+        // The most frequent one if none is consistent
+        assert_eq!(detect_delimiter(&lines(&["a|b|c", "1|2"])), b'|');
+    }
+
+    #[test]
+    fn detect_formats() {
+        let date_time = NaiveDate::from_ymd_opt(2024, 2, 13)
+            .unwrap()
+            .and_hms_milli_opt(14, 15, 16, 250)
+            .unwrap();
+        for pattern in FORMATS {
+            let values = [date_time.format(pattern).to_string()];
+            assert_eq!(
+                detect_format(values.iter()),
+                Format::Naive(pattern.to_owned()),
+                "{pattern}",
+            );
+        }
+        let values = lines(&["2024-02-13T14:15:16.250+03:00"]);
+        assert_eq!(detect_format(values.iter()), Format::Rfc3339);
+        // Unix time isn't detected
+        let values = lines(&["1707833716"]);
+        assert_eq!(
+            detect_format(values.iter()),
+            Format::Naive(FORMATS[0].to_owned()),
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(number("1.5", false), Some(1.5));
+        assert_eq!(number(" -2 ", false), Some(-2.0));
+        assert_eq!(number("1,5", false), None);
+        assert_eq!(number("1,5", true), Some(1.5));
+        assert_eq!(number("1 234,5", true), Some(1234.5));
+        assert_eq!(number("1\u{a0}234,5", true), Some(1234.5));
+        assert_eq!(number("x", true), None);
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse_offset("+05:30"), Some(330));
+        assert_eq!(parse_offset("-03:30"), Some(-210));
+        assert_eq!(parse_offset("-3"), Some(-180));
+        assert_eq!(parse_offset("5.75"), Some(345));
+        assert_eq!(parse_offset("05:60"), None);
+        assert_eq!(parse_offset("abc"), None);
+        for minutes in [MIN_OFFSET, -210, 0, 330, 345, MAX_OFFSET] {
+            assert_eq!(parse_offset(&format_offset(minutes)), Some(minutes));
+        }
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(identifier("0x1A"), Some(26));
+        assert_eq!(identifier("0X1a"), Some(26));
+        assert_eq!(identifier("42"), Some(42));
+        assert_eq!(identifier("0xZZ"), None);
+        assert_eq!(identifier("-1"), None);
+        assert_eq!(identifier(""), None);
+    }
+
+    #[test]
+    fn semicolon_decimal_comma() {
+        let file = File::new(
+            "data.csv",
+            b"\xEF\xBB\xBFIdentifier;Timestamp;Value\n0x1;01.02.2024 10:00;1,5\n0x1;01.02.2024 10:01;2,5\n"
+                .to_vec(),
+        );
+        assert_eq!(file.options.delimiter, b';');
+        assert!(file.options.decimal_comma);
+        assert!(file.options.header);
+        assert_eq!(file.options.format, Format::Naive(FORMATS[4].to_owned()));
+        assert_eq!(file.mapping.identifier, Some(0));
+        assert_eq!(file.mapping.timestamp, 1);
+        assert_eq!(file.mapping.values, [(2, "Value".to_owned())]);
+    }
+
+    #[test]
+    fn headerless() {
+        let file = File::new(
+            "data.csv",
+            b"2024-01-01 00:00:00;1,5\n2024-01-01 00:01:00;2,5\n".to_vec(),
+        );
+        assert!(!file.options.header);
+        assert_eq!(file.columns(), ["column_1", "column_2"]);
+        assert_eq!(file.records().len(), 2);
+        assert_eq!(file.mapping.identifier, None);
+        assert_eq!(file.mapping.timestamp, 0);
+        assert_eq!(file.mapping.values, [(1, "column_2".to_owned())]);
+    }
+
+    #[test]
+    fn no_rows() {
+        let mut file = File::new("data.csv", b"Timestamp,Value\n".to_vec());
+        file.mapping.values = vec![(1, "Value".to_owned())];
+        assert_eq!(file.import().unwrap_err().to_string(), "No rows");
+    }
+}
@@ -0,0 +1,306 @@
+use super::{
+    deserialize,
+    import::{File, is_csv},
+    metadata::MetaDataFrame,
+    spawn,
+};
+use anyhow::{Result, anyhow};
+use egui::{Button, Context, DroppedFile, Label, ProgressBar, RichText, Ui};
+use egui_ext::DroppedFileExt as _;
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::{WARNING, X};
+use std::{
+    any::Any,
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, Sender, channel},
+    },
+};
+use tracing::{error, info};
+
+const PROGRESS_WIDTH: f32 = 128.0;
+/// Time given to the browser to render a stage
+#[cfg(target_arch = "wasm32")]
+const YIELD: std::time::Duration = std::time::Duration::from_millis(16);
+
+/// Background file loader
+///
+/// Reads and parses the dropped files and imports the mapped CSV files out of
+/// the frame update.
+#[derive(Debug)]
+pub(crate) struct Loader {
+    tasks: Vec<Task>,
+    next: u64,
+    sender: Sender<(u64, Message)>,
+    receiver: Receiver<(u64, Message)>,
+}
+
+impl Loader {
+    pub(crate) fn new() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            tasks: Vec::new(),
+            next: 0,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Starts loading the file in the background
+    pub(crate) fn load(&mut self, context: &Context, dropped_file: DroppedFile) {
+        let (id, cancelled) = self.push(dropped_file.name());
+        let send = self.sender(context, id);
+        spawn(async move {
+            let name = dropped_file.name().to_owned();
+            finish(&name, load(dropped_file, &cancelled, &send).await, &send);
+        });
+    }
+
+    /// Starts importing the mapped CSV file in the background
+    pub(crate) fn import(&mut self, context: &Context, file: File) {
+        let (id, cancelled) = self.push(file.name());
+        let send = self.sender(context, id);
+        spawn(async move {
+            let name = file.name().to_owned();
+            finish(&name, import(&file, &cancelled, &send).await, &send);
+        });
+    }
+
+    /// Takes the loaded files, updates the progress of the others
+    pub(crate) fn loaded(&mut self) -> Vec<Loaded> {
+        let mut loaded = Vec::new();
+        while let Ok((id, message)) = self.receiver.try_recv() {
+            // Cancelled tasks are already gone
+            let Some(index) = self.tasks.iter().position(|task| task.id == id) else {
+                continue;
+            };
+            match message {
+                Message::Stage(stage) => self.tasks[index].stage = stage,
+                Message::Loaded(file) => {
+                    self.tasks.remove(index);
+                    loaded.push(file);
+                }
+                Message::Failed(error) => self.tasks[index].error = Some(error),
+            }
+        }
+        loaded
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    fn push(&mut self, name: &str) -> (u64, Arc<AtomicBool>) {
+        let id = self.next;
+        self.next += 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.tasks.push(Task {
+            id,
+            name: name.to_owned(),
+            stage: Stage::Queued,
+            cancelled: cancelled.clone(),
+            error: None,
+        });
+        (id, cancelled)
+    }
+
+    fn sender(&self, context: &Context, id: u64) -> impl Fn(Message) + Send + Sync + 'static {
+        let sender = self.sender.clone();
+        let context = context.clone();
+        move |message| {
+            sender.send((id, message)).ok();
+            context.request_repaint();
+        }
+    }
+}
+
+impl Loader {
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        let mut remove = None;
+        for (index, task) in self.tasks.iter().enumerate() {
+            ui.horizontal(|ui| {
+                match &task.error {
+                    Some(error) => {
+                        // Dismiss
+                        if ui
+                            .add(Button::new(X).small())
+                            .on_hover_localized("loader__dismiss")
+                            .clicked()
+                        {
+                            remove = Some(index);
+                        }
+                        ui.label(
+                            RichText::new(format!("{WARNING} {}", task.name))
+                                .color(ui.visuals().error_fg_color),
+                        )
+                        .on_hover_text(error);
+                    }
+                    None => {
+                        // Cancel
+                        if ui
+                            .add(Button::new(X).small())
+                            .on_hover_localized("loader__cancel")
+                            .clicked()
+                        {
+                            task.cancelled.store(true, Ordering::Relaxed);
+                            remove = Some(index);
+                        }
+                        ui.add(
+                            ProgressBar::new(task.stage.progress())
+                                .desired_width(PROGRESS_WIDTH)
+                                .text(ui.localize(task.stage.text()))
+                                .animate(true),
+                        );
+                        ui.add(Label::new(&task.name).truncate());
+                    }
+                }
+            });
+        }
+        if let Some(index) = remove {
+            self.tasks.remove(index);
+        }
+    }
+}
+
+impl Default for Loader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Loading of a dropped file
+#[derive(Debug)]
+struct Task {
+    id: u64,
+    name: String,
+    stage: Stage,
+    cancelled: Arc<AtomicBool>,
+    error: Option<String>,
+}
+
+/// Loading stage
+#[derive(Clone, Copy, Debug)]
+enum Stage {
+    Queued,
+    Reading,
+    Parsing,
+}
+
+impl Stage {
+    fn progress(self) -> f32 {
+        match self {
+            Self::Queued => 0.0,
+            Self::Reading => 1.0 / 3.0,
+            Self::Parsing => 2.0 / 3.0,
+        }
+    }
+
+    fn text(self) -> &'static str {
+        match self {
+            Self::Queued => "loader__queued",
+            Self::Reading => "loader__reading",
+            Self::Parsing => "loader__parsing",
+        }
+    }
+}
+
+/// Loaded file
+#[derive(Debug)]
+pub(crate) enum Loaded {
+    Frame(MetaDataFrame),
+    /// CSV file waiting for the column mapping
+    Csv(File),
+}
+
+/// Message of the background task
+#[derive(Debug)]
+enum Message {
+    Stage(Stage),
+    Loaded(Loaded),
+    Failed(String),
+}
+
+/// Loads the file, none if cancelled
+///
+/// Parsing itself can't be interrupted, the cancellation is checked between
+/// the stages.
+async fn load(
+    dropped_file: DroppedFile,
+    cancelled: &AtomicBool,
+    send: &impl Fn(Message),
+) -> Result<Option<Loaded>> {
+    send(Message::Stage(Stage::Reading));
+    pause().await;
+    let bytes = dropped_file.bytes()?;
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(None);
+    }
+    send(Message::Stage(Stage::Parsing));
+    pause().await;
+    let name = dropped_file.name();
+    let loaded = if is_csv(name) {
+        Loaded::Csv(unwind(|| Ok(File::new(name, bytes.to_vec())))?)
+    } else {
+        Loaded::Frame(unwind(|| deserialize(name, bytes))?)
+    };
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(None);
+    }
+    Ok(Some(loaded))
+}
+
+/// Imports the mapped CSV file, none if cancelled
+async fn import(
+    file: &File,
+    cancelled: &AtomicBool,
+    send: &impl Fn(Message),
+) -> Result<Option<Loaded>> {
+    send(Message::Stage(Stage::Parsing));
+    pause().await;
+    let frame = unwind(|| file.import())?;
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(None);
+    }
+    Ok(Some(Loaded::Frame(frame)))
+}
+
+fn finish(name: &str, loaded: Result<Option<Loaded>>, send: &impl Fn(Message)) {
+    match loaded {
+        Ok(Some(loaded)) => {
+            info!(file = name, "Loaded");
+            send(Message::Loaded(loaded));
+        }
+        Ok(None) => info!(file = name, "Cancelled"),
+        Err(error) => {
+            error!(file = name, %error);
+            send(Message::Failed(error.to_string()));
+        }
+    }
+}
+
+/// Turns a panic of the parsing into an error, the task would wait forever
+/// otherwise
+fn unwind<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(|panic| anyhow!("Panicked: {}", message(&*panic)))?
+}
+
+fn message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown"
+    }
+}
+
+/// Lets the browser render the stage, the tasks run on its main thread
+#[cfg(target_arch = "wasm32")]
+async fn pause() {
+    tokio_with_wasm::alias::time::sleep(YIELD).await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn pause() {}
@@ -1,3 +1,4 @@
+use egui::{Grid, Label, Ui};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -7,6 +8,7 @@ use std::{
 
 pub const FILE: &str = "File";
 pub const ICON: &str = "Icon";
+pub const KIND: &str = "Kind";
 pub const MAX_TIMESTAMP: &str = "MaxTimestamp";
 pub const MIN_TIMESTAMP: &str = "MinTimestamp";
 pub const NAME: &str = "Name";
@@ -50,3 +52,26 @@ impl PartialEq for MetaDataFrame {
         self.meta == other.meta && self.data.equals_missing(&other.data)
     }
 }
+
+/// Metadata widget
+pub(crate) struct MetadataWidget<'a> {
+    meta: &'a Metadata,
+}
+
+impl<'a> MetadataWidget<'a> {
+    pub(crate) const fn new(meta: &'a Metadata) -> Self {
+        Self { meta }
+    }
+}
+
+impl MetadataWidget<'_> {
+    pub(crate) fn show(self, ui: &mut Ui) {
+        Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+            for (key, value) in self.meta {
+                ui.label(key);
+                ui.add(Label::new(value).wrap());
+                ui.end_row();
+            }
+        });
+    }
+}
@@ -1,25 +1,48 @@
+#[cfg(not(target_arch = "wasm32"))]
+use self::mqtt::{Recording, Simulation, Simulator};
 use self::{
+    alarms::Alarms,
     cloud::GoogleDrive,
+    control::Control,
     data::Data,
-    panes::{Ddoc, Pane, behavior::Behavior},
+    import::Import,
+    loader::{Loaded, Loader},
+    mqtt::{Connection, Retention, Settings as MqttSettings, Staleness},
+    panes::{Ddoc, Kind, Pane, behavior::Behavior},
+    recipe::Recipes,
+    replay::Replay,
+};
+use crate::{
+    app::metadata::{MetaDataFrame, Metadata},
+    localization::ContextExt as _,
 };
-use crate::{app::metadata::MetaDataFrame, localization::ContextExt as _};
 use anyhow::{Error, Result};
-use arrow::temporal_conversions::timestamp_ms_to_datetime;
+use arrow::{
+    array::RecordBatch,
+    compute::concat_batches,
+    ipc::{
+        reader::{FileReader, StreamReader},
+        writer::StreamWriter,
+    },
+    temporal_conversions::timestamp_ms_to_datetime,
+};
 use eframe::{APP_KEY, CreationContext, Storage, get_value, set_value};
 use egui::{
-    Align, Align2, CentralPanel, Color32, ComboBox, DroppedFile, FontDefinitions, Id, LayerId,
-    Layout, Order, RichText, ScrollArea, SidePanel, Spinner, TextStyle, TextWrapMode,
-    TopBottomPanel, Ui, menu::bar, warn_if_debug_build,
+    Align, Align2, CentralPanel, Color32, ComboBox, FontDefinitions, Id, LayerId, Layout, Order,
+    RichText, ScrollArea, SidePanel, Spinner, TextStyle, TextWrapMode, TopBottomPanel, Ui, Window,
+    menu::bar, warn_if_debug_build,
 };
-use egui_ext::{DroppedFileExt, HoveredFileExt, LightDarkButton};
+use egui_ext::{HoveredFileExt, LightDarkButton};
 use egui_l20n::{ResponseExt as _, UiExt};
+#[cfg(not(target_arch = "wasm32"))]
+use egui_phosphor::regular::{FLASK, RECORD};
 use egui_phosphor::{
     Variant, add_to_fonts,
     regular::{
-        ARROW_FAT_LEFT, ARROW_FAT_RIGHT, ARROWS_CLOCKWISE, CLOCK, CLOUD_ARROW_DOWN, DROP_HALF,
-        GRID_FOUR, QUESTION, ROCKET, SIDEBAR, SIDEBAR_SIMPLE, SQUARE_SPLIT_HORIZONTAL,
-        SQUARE_SPLIT_VERTICAL, TABS, THERMOMETER, TRANSLATE, TRASH,
+        ARROW_FAT_LEFT, ARROW_FAT_RIGHT, ARROWS_CLOCKWISE, BELL, BELL_RINGING, CHART_LINE_UP,
+        CLOCK, CLOUD_ARROW_DOWN, GRID_FOUR, PLUGS_CONNECTED, QUESTION, ROCKET, SIDEBAR,
+        SIDEBAR_SIMPLE, SLIDERS_HORIZONTAL, SQUARE_SPLIT_HORIZONTAL, SQUARE_SPLIT_VERTICAL, TABS,
+        TRANSLATE, TRASH, WARNING,
     },
 };
 use egui_tiles::{ContainerKind, Tile, Tree};
@@ -28,12 +51,12 @@ use metadata::{FILE, ICON, MAX_TIMESTAMP, MIN_TIMESTAMP, NAME};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeMap,
     fmt::Write,
     future::Future,
     io::Cursor,
     str,
     sync::mpsc::{Receiver, Sender, channel},
+    time::Duration,
 };
 use tracing::{error, info, instrument, trace};
 
@@ -50,6 +73,9 @@ const YMDHMSZ: &str = "%Y-%m-%d %H:%M:%S %Z";
 const YMDHMS: &str = "%Y-%m-%d %H:%M:%S";
 const MAX_PRECISION: usize = 16;
 const ICON_SIZE: f32 = 32.0;
+const IPC_EXTENSIONS: [&str; 3] = [".arrow", ".feather", ".ipc"];
+const IPC_MAGIC: &[u8] = b"ARROW1";
+const STALENESS_INTERVAL: Duration = Duration::from_secs(1);
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(Deserialize, Serialize)]
@@ -60,7 +86,30 @@ pub struct App {
 
     tree: Tree<Pane>,
     data: Data,
+    retention: Retention,
+    staleness: Staleness,
+    mqtt: MqttSettings,
+    #[cfg(not(target_arch = "wasm32"))]
+    recording: Recording,
+    #[cfg(not(target_arch = "wasm32"))]
+    simulation: Simulation,
+    control: Control,
+    recipes: Recipes,
+    alarms: Alarms,
 
+    #[serde(skip)]
+    connection: Connection,
+    #[serde(skip)]
+    mqtt_window: Option<MqttSettings>,
+    #[serde(skip)]
+    replay: Replay,
+    #[serde(skip)]
+    import: Import,
+    #[serde(skip)]
+    loader: Loader,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    simulator: Simulator,
     #[serde(skip)]
     google_drive: GoogleDrive,
     #[serde(skip)]
@@ -82,6 +131,23 @@ impl Default for App {
             left_panel: true,
             tree: Tree::empty("tree"),
             data: Default::default(),
+            retention: Default::default(),
+            staleness: Default::default(),
+            mqtt: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            recording: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            simulation: Default::default(),
+            control: Default::default(),
+            recipes: Default::default(),
+            alarms: Default::default(),
+            connection: Default::default(),
+            mqtt_window: None,
+            replay: Default::default(),
+            import: Default::default(),
+            loader: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            simulator: Default::default(),
             google_drive: GoogleDrive::new(data_sender, error_sender.clone()),
             data_receiver,
             error_sender,
@@ -99,14 +165,19 @@ impl App {
         add_to_fonts(&mut fonts, Variant::Regular);
         cc.egui_ctx.set_fonts(fonts);
         cc.egui_ctx.set_localizations();
-        mqtt::spawn(&cc.egui_ctx);
 
         // return Default::default();
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        cc.storage
+        let app: Self = cc
+            .storage
             .and_then(|storage| get_value(storage, APP_KEY))
-            .unwrap_or_default()
+            .unwrap_or_default();
+        mqtt::retention(&cc.egui_ctx, app.retention);
+        mqtt::watch(&cc.egui_ctx, &app.staleness);
+        #[cfg(not(target_arch = "wasm32"))]
+        mqtt::record(&cc.egui_ctx, &app.recording);
+        app
     }
 
     fn drag_and_drop(&mut self, ctx: &egui::Context) {
@@ -132,21 +203,29 @@ impl App {
                 Color32::WHITE,
             );
         }
-        // Parse dropped files
+        // Load dropped files in the background
         if let Some(dropped_files) = ctx.input(|input| {
             (!input.raw.dropped_files.is_empty()).then_some(input.raw.dropped_files.clone())
         }) {
             info!(?dropped_files);
             for dropped_file in dropped_files {
-                if let Ok(frame) = deserialize(&dropped_file) {
-                    trace!(?frame);
-                    self.data.add(frame);
-                }
+                self.loader.load(ctx, dropped_file);
             }
         }
     }
 
     fn data(&mut self) {
+        for loaded in self.loader.loaded() {
+            match loaded {
+                Loaded::Frame(frame) => {
+                    trace!(?frame);
+                    self.alarms.evaluate(&frame).ok();
+                    self.data.add(frame);
+                }
+                // Text files need the column mapping
+                Loaded::Csv(file) => self.import.open(file),
+            }
+        }
         // while let Ok(data_frame) = self.data_receiver.try_recv() {
         //     let kind = match data_frame[1].name().as_str() {
         //         NAME_TEMPERATURE => Kind::Dtec,
@@ -173,6 +252,18 @@ impl App {
         // }
     }
 
+    /// Flags the streams which went silent
+    fn watchdog(&mut self, ctx: &egui::Context) {
+        let watchdog = mqtt::watchdog(ctx);
+        let mut watchdog = watchdog.lock();
+        if watchdog.update() > 0 && watchdog.sound() {
+            mqtt::beep();
+        }
+        if !watchdog.is_empty() {
+            ctx.request_repaint_after(STALENESS_INTERVAL);
+        }
+    }
+
     fn error(&mut self) {
         // while let Some(error) = self.error_receiver.recv().await {
         //     error!(%error);
@@ -191,6 +282,54 @@ impl App {
         self.central_panel(ctx);
     }
 
+    fn windows(&mut self, ctx: &egui::Context) {
+        // MQTT
+        if let Some(mut settings) = self.mqtt_window.take() {
+            let mut open = true;
+            let mut connect = false;
+            Window::new(format!("{PLUGS_CONNECTED} MQTT"))
+                .open(&mut open)
+                .show(ctx, |ui| {
+                    settings.show(ui);
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(ui.localize("mqtt__connect"))
+                            .on_hover_localized("mqtt__connect.hover")
+                            .clicked()
+                        {
+                            connect = true;
+                        }
+                        if ui
+                            .button(ui.localize("mqtt__reset"))
+                            .on_hover_localized("mqtt__reset.hover")
+                            .clicked()
+                        {
+                            settings = Default::default();
+                        }
+                    });
+                });
+            if connect {
+                self.mqtt = settings.clone();
+            }
+            if open {
+                self.mqtt_window = Some(settings);
+            }
+        }
+        // Replay
+        self.replay.window(ctx);
+        // Control
+        self.control.window(ctx, &mut self.connection, &self.mqtt);
+        // Recipes
+        self.recipes.window(ctx);
+        // Alarms
+        self.alarms.window(ctx, &self.data.frames);
+        // Import
+        if let Some(file) = self.import.window(ctx) {
+            self.loader.import(ctx, file);
+        }
+    }
+
     // Bottom panel
     fn bottom_panel(&mut self, ctx: &egui::Context) {
         TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
@@ -198,6 +337,9 @@ impl App {
                 warn_if_debug_build(ui);
                 ui.label(RichText::new(env!("CARGO_PKG_VERSION")).small());
                 ui.separator();
+                // MQTT
+                self.connection.status().show(ui);
+                ui.separator();
             });
         });
     }
@@ -205,7 +347,10 @@ impl App {
     // Central panel
     fn central_panel(&mut self, ctx: &egui::Context) {
         CentralPanel::default().show(ctx, |ui| {
-            let mut behavior = Behavior::new();
+            let mut behavior = Behavior::new(
+                mqtt::watchdog(ctx).lock().stale_topics(),
+                ui.visuals().error_fg_color,
+            );
             self.tree.ui(&mut behavior, ui);
             if let Some(id) = behavior.close.take() {
                 self.tree.tiles.remove(id);
@@ -219,7 +364,8 @@ impl App {
             .resizable(true)
             .show_animated(ctx, self.left_panel, |ui| {
                 ScrollArea::vertical().show(ui, |ui| {
-                    self.data.show(ui, &mut self.tree);
+                    self.data
+                        .show(ui, &mut self.tree, &mut self.replay, &mut self.loader);
                 });
             });
     }
@@ -248,6 +394,10 @@ impl App {
                     .clicked()
                 {
                     *self = Default::default();
+                    mqtt::retention(ui.ctx(), self.retention);
+                    mqtt::watch(ui.ctx(), &self.staleness);
+                    #[cfg(not(target_arch = "wasm32"))]
+                    mqtt::record(ui.ctx(), &self.recording);
                 }
                 ui.separator();
                 if ui
@@ -305,22 +455,40 @@ impl App {
                 }
                 ui.separator();
                 // In real time
+                let stale = mqtt::watchdog(ui.ctx()).lock().stale_topics();
                 let mut toggle = |ui: &mut Ui, pane: Pane| {
                     let tile_id = self.tree.tiles.find_pane_by(|candidate| {
                         candidate.kind == pane.kind
+                            && candidate.topic == pane.topic
                             && candidate.is_real_time() == pane.is_real_time()
                     });
-                    if ui
-                        .selectable_label(tile_id.is_some(), ui.localize(pane.text()))
-                        .on_hover_text(ui.localize(pane.hover_text()))
-                        .clicked()
-                    {
+                    let text = if pane.kind == Kind::Generic {
+                        RichText::new(&pane.topic)
+                    } else {
+                        RichText::new(ui.localize(pane.text()))
+                    };
+                    let is_stale = pane.topic().is_some_and(|topic| stale.contains(topic));
+                    let text = if is_stale {
+                        RichText::new(format!("{WARNING} {}", text.text()))
+                            .color(ui.visuals().error_fg_color)
+                    } else {
+                        text
+                    };
+                    let mut response = ui.selectable_label(tile_id.is_some(), text);
+                    if pane.kind != Kind::Generic {
+                        response = response.on_hover_text(ui.localize(pane.hover_text()));
+                    }
+                    if is_stale {
+                        response = response.on_hover_localized("staleness__stale");
+                    }
+                    if response.clicked() {
                         if let Some(id) = tile_id {
                             self.tree.tiles.remove(id);
                         } else {
                             self.tree.insert_pane::<VERTICAL>(pane);
                         }
                     }
+                    response
                 };
                 ui.menu_button(RichText::new(CLOCK).size(ICON_SIZE), |ui| {
                     // Temperature
@@ -340,9 +508,116 @@ impl App {
                     )
                     .response
                     .on_disabled_hover_localized("digital_disolved_oxygen_controller.hover");
+                    // Discovered topics
+                    ui.menu_button(ui.localize("topics"), |ui| {
+                        let topics = mqtt::topics(ui.ctx());
+                        let topics = topics.lock();
+                        if topics.is_empty() {
+                            ui.label(ui.localize("topics__none"));
+                        }
+                        for (topic, seen) in topics.iter() {
+                            toggle(ui, Pane::generic(topic.clone()))
+                                .on_hover_ui(|ui| seen.show(ui));
+                        }
+                    })
+                    .response
+                    .on_hover_localized("topics.hover");
+                    ui.separator();
+                    // Retention
+                    let retention = self.retention;
+                    self.retention.show(ui);
+                    if self.retention != retention {
+                        mqtt::retention(ui.ctx(), self.retention);
+                    }
+                    ui.separator();
+                    // Staleness
+                    ui.menu_button(ui.localize("staleness"), |ui| {
+                        let staleness = self.staleness.clone();
+                        self.staleness.show(ui);
+                        if self.staleness != staleness {
+                            mqtt::watch(ui.ctx(), &self.staleness);
+                        }
+                    })
+                    .response
+                    .on_hover_localized("staleness.hover");
                 })
                 .response
                 .on_hover_text(ui.localize("in_real_time"));
+                // Recording
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.menu_button(RichText::new(RECORD).size(ICON_SIZE), |ui| {
+                    let recording = self.recording.clone();
+                    self.recording.show(ui);
+                    if self.recording != recording {
+                        mqtt::record(ui.ctx(), &self.recording);
+                    }
+                })
+                .response
+                .on_hover_localized("recording.hover");
+                // Simulation
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.menu_button(RichText::new(FLASK).size(ICON_SIZE), |ui| {
+                    self.simulation.show(ui);
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if self.simulator.is_running() {
+                            if ui
+                                .button(ui.localize("simulation__stop"))
+                                .on_hover_localized("simulation__stop.hover")
+                                .clicked()
+                            {
+                                self.simulator.stop();
+                            }
+                        } else if ui
+                            .button(ui.localize("simulation__start"))
+                            .on_hover_localized("simulation__start.hover")
+                            .clicked()
+                        {
+                            if let Err(error) = self.simulator.start(&self.mqtt, &self.simulation) {
+                                error!(%error);
+                            }
+                        }
+                        ui.label(ui.localize(&format!(
+                            "simulation__published?count={}",
+                            self.simulator.published(),
+                        )));
+                    });
+                })
+                .response
+                .on_hover_localized("simulation.hover");
+                // Control
+                ui.toggle_value(
+                    &mut self.control.open,
+                    RichText::new(SLIDERS_HORIZONTAL).size(ICON_SIZE),
+                )
+                .on_hover_localized("control.hover");
+                // Recipes
+                ui.toggle_value(
+                    &mut self.recipes.open,
+                    RichText::new(CHART_LINE_UP).size(ICON_SIZE),
+                )
+                .on_hover_localized("recipe.hover");
+                // Alarms
+                let unacknowledged = self.alarms.unacknowledged();
+                let text = if unacknowledged > 0 {
+                    RichText::new(BELL_RINGING).color(ui.visuals().error_fg_color)
+                } else {
+                    RichText::new(BELL)
+                };
+                ui.toggle_value(&mut self.alarms.open, text.size(ICON_SIZE))
+                    .on_hover_localized("alarms.hover")
+                    .on_hover_text(
+                        ui.localize(&format!("alarms__unacknowledged?count={unacknowledged}")),
+                    );
+                // MQTT
+                let mut open = self.mqtt_window.is_some();
+                if ui
+                    .toggle_value(&mut open, RichText::new(PLUGS_CONNECTED).size(ICON_SIZE))
+                    .on_hover_localized("mqtt.hover")
+                    .changed()
+                {
+                    self.mqtt_window = open.then(|| self.mqtt.clone());
+                }
                 // // Open cloud saved
                 // ui.menu_button(RichText::new(CLOUD_ARROW_DOWN).size(ICON_SIZE), |ui| {
                 //     self.google_drive.ui(ui);
@@ -367,7 +642,20 @@ impl eframe::App for App {
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.panels(ctx);
+        self.windows(ctx);
         self.drag_and_drop(ctx);
+        self.connection.update(ctx, &self.mqtt);
+        self.replay.update(ctx);
+        self.control.update(ctx);
+        self.recipes
+            .update(ctx, &mut self.control, &mut self.connection, &self.mqtt);
+        self.alarms.update(ctx);
+        self.watchdog(ctx);
+        // Finish the recorded files before the window closes
+        #[cfg(not(target_arch = "wasm32"))]
+        if ctx.input(|input| input.viewport().close_requested()) {
+            mqtt::recorder(ctx).lock().close_all();
+        }
         self.data();
         self.error();
         // localization::update(ctx);
@@ -377,40 +665,118 @@ impl eframe::App for App {
     }
 }
 
-#[instrument(err)]
-fn deserialize(dropped_file: &DroppedFile) -> Result<MetaDataFrame> {
-    let bytes = dropped_file.bytes()?;
-    let mut reader = ParquetReader::new(Cursor::new(bytes));
-    let meta = reader.get_metadata()?;
-    // if let Some(meta) = &meta.key_value_metadata {
-    //     for key_value in meta {
-    //         println!("name: {} {:?}", key_value.key, key_value.value);
-    //     }
-    // }
-    // let mut meta = Metadata::default();
-    let mut meta = BTreeMap::new();
-    meta.insert(FILE.to_owned(), dropped_file.name().to_owned());
-    let data = reader.finish()?;
-    let last = data.width() - 1;
-    let name = data[last].name().to_lowercase();
-    // Icon
-    match &*name {
-        NAME_TEMPERATURE => meta.insert(ICON.to_owned(), THERMOMETER.to_owned()),
-        NAME_TURBIDITY => meta.insert(ICON.to_owned(), DROP_HALF.to_owned()),
-        _ => meta.insert(ICON.to_owned(), QUESTION.to_owned()),
-    };
-    // Timestamp
-    if let Some((min, max)) = data["Timestamp"].datetime()?.min_max() {
-        if let Some(min) = timestamp_ms_to_datetime(min) {
-            meta.insert(MIN_TIMESTAMP.to_owned(), min.format(YMDHMS).to_string());
+#[instrument(skip(bytes), err)]
+fn deserialize<T: AsRef<[u8]> + Send + Sync>(file: &str, bytes: T) -> Result<MetaDataFrame> {
+    let name = file.to_lowercase();
+    // Key/value metadata written by the loggers
+    let mut metadata = Metadata::new();
+    let data = if IPC_EXTENSIONS
+        .iter()
+        .any(|extension| name.ends_with(extension))
+    {
+        // The file format starts with the magic, the stream format doesn't
+        if bytes.as_ref().starts_with(IPC_MAGIC) {
+            let reader = FileReader::try_new(Cursor::new(bytes.as_ref()), None)?;
+            metadata.extend(reader.schema().metadata().clone());
+            IpcReader::new(Cursor::new(bytes)).finish()?
+        } else {
+            let reader = StreamReader::try_new(Cursor::new(bytes.as_ref()), None)?;
+            metadata.extend(reader.schema().metadata().clone());
+            IpcStreamReader::new(Cursor::new(bytes)).finish()?
         }
-        if let Some(max) = timestamp_ms_to_datetime(max) {
-            meta.insert(MAX_TIMESTAMP.to_owned(), max.format(YMDHMS).to_string());
+    } else {
+        let mut reader = ParquetReader::new(Cursor::new(bytes));
+        if let Some(key_values) = &reader.get_metadata()?.key_value_metadata {
+            for key_value in key_values {
+                if let Some(value) = &key_value.value {
+                    metadata.insert(key_value.key.clone(), value.clone());
+                }
+            }
+        }
+        reader.finish()?
+    };
+    // Derived from the data, these override the written ones
+    metadata.extend(meta(&data)?);
+    metadata.insert(FILE.to_owned(), file.to_owned());
+    Ok(MetaDataFrame::new(metadata, data))
+}
+
+/// Metadata derived from the data frame columns
+fn meta(data: &DataFrame) -> Result<Metadata> {
+    let mut meta = Metadata::new();
+    let names = values(data)
+        .map(|column| column.name().to_lowercase())
+        .collect::<Vec<_>>();
+    // Icon of the first known device value
+    let icon = names
+        .iter()
+        .find_map(|name| Kind::from_name(name))
+        .map_or(QUESTION, |kind| kind.icon());
+    meta.insert(ICON.to_owned(), icon.to_owned());
+    // Timestamp, a reduced frame may have no rows left
+    if !data.is_empty() {
+        // Loaded frames may keep another time unit
+        let timestamps = data
+            .column("Timestamp")?
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?;
+        if let Some((min, max)) = timestamps.datetime()?.min_max() {
+            if let Some(min) = timestamp_ms_to_datetime(min) {
+                meta.insert(MIN_TIMESTAMP.to_owned(), min.format(YMDHMS).to_string());
+            }
+            if let Some(max) = timestamp_ms_to_datetime(max) {
+                meta.insert(MAX_TIMESTAMP.to_owned(), max.format(YMDHMS).to_string());
+            }
         }
     }
     // Name
-    meta.insert(NAME.to_owned(), name);
-    Ok(MetaDataFrame::new(meta, data))
+    meta.insert(NAME.to_owned(), names.join(","));
+    Ok(meta)
+}
+
+/// Topic of the value column, the device topic of a known column and the
+/// column name otherwise
+fn topic(column: &str) -> String {
+    Kind::from_name(column).map_or_else(|| column.to_lowercase(), |kind| kind.topic().to_owned())
+}
+
+/// Numeric value columns, all but the identifier and the timestamp
+fn values(data: &DataFrame) -> impl Iterator<Item = &Column> {
+    data.get_columns().iter().filter(|column| {
+        let name = column.name().as_str();
+        name != "Identifier" && name != "Timestamp" && column.dtype().is_numeric()
+    })
+}
+
+/// Arrow batches to a data frame
+///
+/// The IPC stream is the cheapest common format of arrow and polars.
+fn from_batches<'a>(batches: impl IntoIterator<Item = &'a RecordBatch>) -> Result<DataFrame> {
+    let mut batches = batches.into_iter().peekable();
+    let Some(front) = batches.peek() else {
+        return Ok(DataFrame::empty());
+    };
+    let mut bytes = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut bytes, &front.schema())?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()?;
+    drop(writer);
+    let mut data = IpcStreamReader::new(Cursor::new(bytes)).finish()?;
+    data.rechunk_mut();
+    Ok(data)
+}
+
+/// Data frame to an arrow batch, the inverse of [`from_batches`]
+fn to_batch(data: &mut DataFrame) -> Result<RecordBatch> {
+    let mut bytes = Vec::new();
+    IpcStreamWriter::new(&mut bytes)
+        .with_compat_level(CompatLevel::oldest())
+        .finish(data)?;
+    let reader = StreamReader::try_new(Cursor::new(bytes), None)?;
+    let schema = reader.schema();
+    let batches = reader.collect::<Result<Vec<_>, _>>()?;
+    Ok(concat_batches(&schema, &batches)?)
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -423,9 +789,15 @@ fn spawn<F: Future<Output = ()> + 'static>(f: F) {
     wasm_bindgen_futures::spawn_local(f);
 }
 
+mod alarms;
 mod cloud;
 mod computers;
+mod control;
 mod data;
+mod import;
+mod loader;
 mod metadata;
 mod mqtt;
 mod panes;
+mod recipe;
+mod replay;
@@ -0,0 +1,223 @@
+use super::TIMESTAMP;
+use crate::app::from_batches;
+use anyhow::Result;
+use arrow::{
+    array::{AsArray as _, RecordBatch},
+    compute::max,
+    datatypes::TimestampMillisecondType,
+};
+use egui::{ComboBox, DragValue, Grid, Ui};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use polars::prelude::DataFrame;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const MEBIBYTE: usize = 1024 * 1024;
+const MAX_ROWS: usize = 100_000_000;
+/// 30 days
+const MAX_AGE: u64 = 30 * 24 * 60 * 60;
+/// 16 GiB
+const MAX_MEMORY: usize = 16 * 1024;
+
+/// Topic buffer
+///
+/// Keeps the incoming record batches of a topic until they fall out of the
+/// [`Retention`] limit.
+#[derive(Debug, Default)]
+pub(crate) struct Buffer {
+    batches: VecDeque<RecordBatch>,
+    version: u64,
+    /// Version of the last clear, the older batches are gone
+    reset: u64,
+    /// Data frame of the version, shared by the panes and the alarms
+    cache: Option<(u64, DataFrame)>,
+}
+
+impl Buffer {
+    /// Incremented on every appended batch
+    pub(crate) const fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    pub(crate) fn rows(&self) -> usize {
+        self.batches.iter().map(RecordBatch::num_rows).sum()
+    }
+
+    pub(crate) fn push(&mut self, batch: RecordBatch, retention: Retention) {
+        // A schema change means a new stream, the old batches can't be concatenated with it
+        if self
+            .batches
+            .front()
+            .is_some_and(|front| front.schema() != batch.schema())
+        {
+            self.batches.clear();
+            self.reset = self.version;
+        }
+        self.batches.push_back(batch);
+        self.retain(retention);
+        self.version += 1;
+    }
+
+    /// Drops the buffered batches, e.g. when a replay seeks back
+    pub(crate) fn clear(&mut self) {
+        self.batches.clear();
+        self.version += 1;
+        self.reset = self.version;
+    }
+
+    /// Data frame of the batches appended after the version, none if the
+    /// buffer was cleared since
+    pub(crate) fn appended(&self, version: u64) -> Result<Option<DataFrame>> {
+        if version < self.reset || version > self.version {
+            return Ok(None);
+        }
+        // One version per appended batch since the clear
+        let count = ((self.version - version) as usize).min(self.batches.len());
+        let batches = self.batches.range(self.batches.len() - count..);
+        Ok(Some(from_batches(batches)?))
+    }
+
+    /// Concatenates the buffered batches into a data frame, once per version
+    pub(crate) fn data_frame(&mut self) -> Result<DataFrame> {
+        if let Some((version, data)) = &self.cache {
+            if *version == self.version {
+                return Ok(data.clone());
+            }
+        }
+        let data = from_batches(&self.batches)?;
+        self.cache = Some((self.version, data.clone()));
+        Ok(data)
+    }
+
+    fn retain(&mut self, retention: Retention) {
+        match retention {
+            Retention::Rows(rows) => {
+                let mut total = self.rows();
+                while total > rows {
+                    let Some(front) = self.batches.pop_front() else {
+                        break;
+                    };
+                    let excess = total - rows;
+                    if front.num_rows() > excess {
+                        self.batches
+                            .push_front(front.slice(excess, front.num_rows() - excess));
+                        break;
+                    }
+                    total -= front.num_rows();
+                }
+            }
+            Retention::Age(seconds) => {
+                let Some(last) = self.batches.back().and_then(max_timestamp) else {
+                    return;
+                };
+                let cutoff =
+                    last.saturating_sub((seconds.min(MAX_AGE) as i64).saturating_mul(1000));
+                while self.batches.len() > 1
+                    && self
+                        .batches
+                        .front()
+                        .and_then(max_timestamp)
+                        .is_some_and(|max| max < cutoff)
+                {
+                    self.batches.pop_front();
+                }
+            }
+            Retention::Memory(mebibytes) => {
+                let budget = mebibytes.saturating_mul(MEBIBYTE);
+                let mut total: usize = self
+                    .batches
+                    .iter()
+                    .map(RecordBatch::get_array_memory_size)
+                    .sum();
+                while self.batches.len() > 1 && total > budget {
+                    if let Some(front) = self.batches.pop_front() {
+                        total -= front.get_array_memory_size();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Retention limit of the topic buffers
+#[derive(Clone, Copy, Debug, Deserialize, Hash, PartialEq, Serialize)]
+pub(crate) enum Retention {
+    /// Maximum number of rows
+    Rows(usize),
+    /// Maximum age relative to the last timestamp, seconds
+    Age(u64),
+    /// Maximum memory size, mebibytes
+    Memory(usize),
+}
+
+impl Retention {
+    pub(crate) const ROWS: Self = Self::Rows(100_000);
+    pub(crate) const AGE: Self = Self::Age(12 * 60 * 60);
+    pub(crate) const MEMORY: Self = Self::Memory(64);
+
+    pub(crate) fn text(&self) -> &'static str {
+        match self {
+            Self::Rows(_) => "retention__rows",
+            Self::Age(_) => "retention__age",
+            Self::Memory(_) => "retention__memory",
+        }
+    }
+
+    pub(crate) fn hover_text(&self) -> &'static str {
+        match self {
+            Self::Rows(_) => "retention__rows.hover",
+            Self::Age(_) => "retention__age.hover",
+            Self::Memory(_) => "retention__memory.hover",
+        }
+    }
+}
+
+impl Retention {
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            ui.label(ui.localize("retention"));
+            ComboBox::from_id_salt("Retention")
+                .selected_text(ui.localize(self.text()))
+                .show_ui(ui, |ui| {
+                    for retention in [Self::ROWS, Self::AGE, Self::MEMORY] {
+                        let selected = self.text() == retention.text();
+                        if ui
+                            .selectable_label(selected, ui.localize(retention.text()))
+                            .on_hover_localized(retention.hover_text())
+                            .clicked()
+                            && !selected
+                        {
+                            *self = retention;
+                        }
+                    }
+                })
+                .response
+                .on_hover_localized(self.hover_text());
+            ui.end_row();
+
+            ui.label("");
+            match self {
+                Self::Rows(rows) => ui.add(DragValue::new(rows).range(1..=MAX_ROWS)),
+                Self::Age(seconds) => ui.add(DragValue::new(seconds).range(1..=MAX_AGE)),
+                Self::Memory(mebibytes) => ui.add(DragValue::new(mebibytes).range(1..=MAX_MEMORY)),
+            }
+            .on_hover_localized(self.hover_text());
+        });
+    }
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self::AGE
+    }
+}
+
+fn max_timestamp(batch: &RecordBatch) -> Option<i64> {
+    max(batch
+        .column_by_name(TIMESTAMP)?
+        .as_primitive_opt::<TimestampMillisecondType>()?)
+}
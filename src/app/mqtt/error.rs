@@ -0,0 +1,83 @@
+use arrow::{datatypes::DataType, error::ArrowError};
+use std::fmt::{self, Display, Formatter};
+
+/// Payload error
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Error {
+    /// The payload can't be decoded
+    Decode(String),
+    /// The payload schema doesn't match the topic schema
+    Schema(Mismatch),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Decode(error) => write!(f, "decode: {error}"),
+            Self::Schema(mismatch) => write!(f, "schema: {mismatch}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ArrowError> for Error {
+    fn from(value: ArrowError) -> Self {
+        Self::Decode(value.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Decode(value.to_string())
+    }
+}
+
+impl From<Mismatch> for Error {
+    fn from(value: Mismatch) -> Self {
+        Self::Schema(value)
+    }
+}
+
+/// Schema mismatch
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Mismatch {
+    Fields {
+        expected: usize,
+        found: usize,
+    },
+    Missing {
+        expected: &'static str,
+    },
+    Name {
+        index: usize,
+        expected: &'static str,
+        found: String,
+    },
+    Type {
+        name: String,
+        expected: &'static str,
+        found: DataType,
+    },
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Fields { expected, found } => {
+                write!(f, "expected {expected} fields, found {found}")
+            }
+            Self::Missing { expected } => write!(f, "missing {expected} field"),
+            Self::Name {
+                index,
+                expected,
+                found,
+            } => write!(f, "expected field {index} named {expected}, found {found}"),
+            Self::Type {
+                name,
+                expected,
+                found,
+            } => write!(f, "expected field {name} of {expected} type, found {found}"),
+        }
+    }
+}
@@ -0,0 +1,577 @@
+pub(crate) use self::{
+    buffer::{Buffer, Retention},
+    error::{Error, Mismatch},
+    payload::Payload,
+    settings::Settings,
+    status::{State, Status},
+    topics::Topics,
+    watchdog::{Staleness, Watchdog, beep},
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use self::{
+    recorder::{Recorder, Recording},
+    simulator::{Simulation, Simulator},
+};
+
+use self::status::Backoff;
+use crate::app::{
+    NAME_DDOC_C1, NAME_DDOC_C2, NAME_DDOC_T1, NAME_DDOC_T2, NAME_DDOC_V1, NAME_DDOC_V2,
+    NAME_TEMPERATURE, NAME_TURBIDITY,
+};
+
+#[cfg(target_arch = "wasm32")]
+use self::web::Client;
+#[cfg(not(target_arch = "wasm32"))]
+use rumqttc::Client;
+
+use anyhow::{Result, bail};
+use arrow::{
+    array::RecordBatch,
+    datatypes::{DataType, Field, Schema, TimeUnit},
+};
+use bytes::Bytes;
+use egui::{Context, Id, mutex::Mutex};
+#[cfg(target_arch = "wasm32")]
+use std::fmt::Display;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{iter::zip, sync::Arc, time::Duration};
+use tracing::{error, instrument, trace};
+
+pub(crate) const TOPIC: &str = "#";
+pub(crate) const TOPIC_ATUC: &str = "atuc";
+pub(crate) const TOPIC_DDOC_C1: &str = "ddoc/c1"; // mA
+pub(crate) const TOPIC_DDOC_C2: &str = "ddoc/c2"; // mA
+pub(crate) const TOPIC_DDOC_T1: &str = "ddoc/t1"; // °C
+pub(crate) const TOPIC_DDOC_T2: &str = "ddoc/t2"; // °C
+pub(crate) const TOPIC_DDOC_V1: &str = "ddoc/v1"; // mg/L
+pub(crate) const TOPIC_DDOC_V2: &str = "ddoc/v2"; // %
+pub(crate) const TOPIC_DTEC: &str = "dtec";
+/// Command, acknowledgement and audit subtopics of the device controllers
+pub(crate) const TOPIC_COMMAND: &str = "command";
+pub(crate) const TOPIC_ACK: &str = "ack";
+pub(crate) const TOPIC_AUDIT: &str = "audit";
+/// Device topics
+pub(crate) const TOPICS: [&str; 8] = [
+    TOPIC_ATUC,
+    TOPIC_DDOC_C1,
+    TOPIC_DDOC_C2,
+    TOPIC_DDOC_T1,
+    TOPIC_DDOC_T2,
+    TOPIC_DDOC_V1,
+    TOPIC_DDOC_V2,
+    TOPIC_DTEC,
+];
+
+const IDENTIFIER: &str = "Identifier";
+const TIMESTAMP: &str = "Timestamp";
+
+const RETENTION: &str = "Retention";
+const TOPICS_ID: &str = "Topics";
+const ACKS: &str = "Acks";
+const WATCHDOG: &str = "Watchdog";
+#[cfg(not(target_arch = "wasm32"))]
+const RECORDER: &str = "Recorder";
+const CAPACITY: usize = 9;
+
+/// MQTT connection
+///
+/// Connects to the broker with the given settings and reconnects whenever they
+/// change or the connection drops.
+#[derive(Default)]
+pub(crate) struct Connection {
+    settings: Option<Settings>,
+    client: Option<Client>,
+    status: Arc<Mutex<Status>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    stop: Arc<AtomicBool>,
+    #[cfg(target_arch = "wasm32")]
+    backoff: Backoff,
+    /// Time of the next reconnection attempt, seconds
+    #[cfg(target_arch = "wasm32")]
+    retry: Option<f64>,
+}
+
+impl Connection {
+    pub(crate) fn update(&mut self, context: &Context, settings: &Settings) {
+        if self.settings.as_ref() != Some(settings) {
+            self.disconnect();
+            self.status = Default::default();
+            self.settings = Some(settings.clone());
+            watchdog(context).lock().clear();
+            self.connect(context, settings);
+        }
+        self.poll(context);
+    }
+
+    pub(crate) fn status(&self) -> Status {
+        self.status.lock().clone()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Connection {
+    fn connect(&mut self, context: &Context, settings: &Settings) {
+        match Client::connect(context, settings) {
+            Ok(client) => self.client = Some(client),
+            Err(error) => {
+                error!(%error);
+                self.retry(context, error);
+            }
+        }
+    }
+
+    fn disconnect(&mut self) {
+        if let Some(mut client) = self.client.take() {
+            client.disconnect();
+        }
+        self.backoff.reset();
+        self.retry = None;
+    }
+
+    fn poll(&mut self, context: &Context) {
+        let Some(settings) = self.settings.clone() else {
+            return;
+        };
+        if let Some(client) = &mut self.client {
+            match client.poll(context, &settings, &self.status) {
+                Ok(()) => {
+                    if self.status.lock().state == State::Connected {
+                        self.backoff.reset();
+                    }
+                }
+                Err(error) => {
+                    error!(%error);
+                    self.client = None;
+                    self.retry(context, error);
+                }
+            }
+        } else if self
+            .retry
+            .is_some_and(|retry| context.input(|input| input.time) >= retry)
+        {
+            self.retry = None;
+            self.connect(context, &settings);
+        }
+    }
+
+    /// Publishes the payload on the topic relative to the prefix
+    pub(crate) fn publish(
+        &mut self,
+        context: &Context,
+        topic: &str,
+        retain: bool,
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        let (Some(settings), Some(client)) = (&self.settings, &mut self.client) else {
+            bail!("Not connected");
+        };
+        client.publish(
+            context,
+            &settings.topic(topic),
+            settings.qos.into(),
+            retain,
+            &payload,
+        )
+    }
+
+    fn retry(&mut self, context: &Context, error: impl Display) {
+        let delay = self.backoff.next();
+        self.status.lock().reconnecting(self.backoff.attempt, error);
+        self.retry = Some(context.input(|input| input.time) + delay.as_secs_f64());
+        context.request_repaint_after(delay);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Connection {
+    fn connect(&mut self, context: &Context, settings: &Settings) {
+        let options = match options(settings) {
+            Ok(options) => options,
+            Err(error) => {
+                error!(%error);
+                self.status.lock().failed(error);
+                return;
+            }
+        };
+        let (client, connection) = Client::new(options, CAPACITY);
+        self.stop = Default::default();
+        let context = context.clone();
+        let settings = settings.clone();
+        let status = self.status.clone();
+        let stop = self.stop.clone();
+        let handler_client = client.clone();
+        std::thread::spawn(move || {
+            handler(context, settings, handler_client, connection, status, stop)
+        });
+        self.client = Some(client);
+    }
+
+    fn disconnect(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(client) = self.client.take() {
+            client.try_disconnect().ok();
+        }
+    }
+
+    fn poll(&mut self, _context: &Context) {}
+
+    /// Publishes the payload on the topic relative to the prefix
+    pub(crate) fn publish(
+        &mut self,
+        _context: &Context,
+        topic: &str,
+        retain: bool,
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        let (Some(settings), Some(client)) = (&self.settings, &self.client) else {
+            bail!("Not connected");
+        };
+        client.try_publish(settings.topic(topic), settings.qos.into(), retain, payload)?;
+        Ok(())
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn options(settings: &Settings) -> Result<rumqttc::MqttOptions> {
+    use rumqttc::{
+        MqttOptions, TlsConfiguration, Transport,
+        tokio_rustls::rustls::{ClientConfig, RootCertStore},
+    };
+
+    let mut options = MqttOptions::new(settings.client_id(), &settings.host, settings.port);
+    options.set_keep_alive(Duration::from_secs(settings.keep_alive));
+    if !settings.username.is_empty() {
+        options.set_credentials(&settings.username, &settings.password);
+    }
+    if settings.tls {
+        let configuration = if settings.ca.is_empty() {
+            // The default configuration panics without the platform certificates
+            let mut roots = RootCertStore::empty();
+            for certificate in rustls_native_certs::load_native_certs()? {
+                roots.add(certificate)?;
+            }
+            let config = ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            TlsConfiguration::Rustls(Arc::new(config))
+        } else {
+            TlsConfiguration::Simple {
+                ca: std::fs::read(&settings.ca)?,
+                alpn: None,
+                client_auth: None,
+            }
+        };
+        options.set_transport(Transport::Tls(configuration));
+    }
+    Ok(options)
+}
+
+/// Polls the connection, the event loop reconnects on the next poll after an
+/// error
+#[cfg(not(target_arch = "wasm32"))]
+#[instrument(skip_all)]
+fn handler(
+    context: Context,
+    settings: Settings,
+    client: Client,
+    mut connection: rumqttc::Connection,
+    status: Arc<Mutex<Status>>,
+    stop: Arc<AtomicBool>,
+) {
+    use rumqttc::{Event, Incoming};
+
+    let mut backoff = Backoff::default();
+    for event in connection.iter() {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+        match event {
+            Ok(Event::Incoming(Incoming::ConnAck(_))) => {
+                backoff.reset();
+                status.lock().connected();
+                // Clean session, so subscribe on every connection
+                if let Err(error) = client.try_subscribe(settings.topic(TOPIC), settings.qos.into())
+                {
+                    error!(%error);
+                }
+                context.request_repaint();
+            }
+            Ok(Event::Incoming(Incoming::Publish(publish))) => {
+                status.lock().message();
+                match receive(&context, &settings, &publish.topic, publish.payload) {
+                    Ok(()) => status.lock().accept(&publish.topic),
+                    Err(error) => status.lock().reject(&publish.topic, error),
+                }
+            }
+            Ok(_) => {}
+            Err(error) => {
+                error!(%error);
+                let delay = backoff.next();
+                status.lock().reconnecting(backoff.attempt, error);
+                context.request_repaint();
+                std::thread::sleep(delay);
+            }
+        }
+    }
+    if !stop.load(Ordering::Relaxed) {
+        status.lock().failed("Connection closed");
+        context.request_repaint();
+    }
+}
+
+/// Decodes the published payload into the topic buffer
+#[instrument(skip(context, settings, payload), err)]
+fn receive(
+    context: &Context,
+    settings: &Settings,
+    topic: &str,
+    payload: Bytes,
+) -> Result<(), Error> {
+    let Some(topic) = settings.strip(topic) else {
+        error!("Unexpected MQTT topic {topic}");
+        return Ok(());
+    };
+    // Control subtopics carry commands, not device batches
+    match topic.rsplit_once('/').map(|(_, subtopic)| subtopic) {
+        Some(TOPIC_ACK) => {
+            acks(context).lock().push(payload);
+            context.request_repaint();
+            return Ok(());
+        }
+        Some(TOPIC_COMMAND | TOPIC_AUDIT) => return Ok(()),
+        _ => {}
+    }
+    let result = read(settings, topic, payload);
+    topics(context).lock().record(topic, result.as_ref());
+    let batch = result?;
+    trace!("batch: {batch:?}");
+    // A schema-only stream carries no rows
+    if batch.num_rows() > 0 {
+        feed(context, topic, batch);
+    }
+    Ok(())
+}
+
+/// Feeds the batch into the live path: the recorder and the topic buffer
+pub(crate) fn feed(context: &Context, topic: &str, batch: RecordBatch) {
+    #[cfg(not(target_arch = "wasm32"))]
+    recorder(context).lock().write(topic, &batch);
+    watchdog(context).lock().feed(topic, &batch);
+    write(context, topic, batch);
+}
+
+/// Decodes the payload with the topic payload settings
+#[instrument(skip(settings, bytes), err)]
+fn read(settings: &Settings, topic: &str, bytes: Bytes) -> Result<RecordBatch, Error> {
+    let payload = settings.payloads.get(topic).cloned().unwrap_or_default();
+    let batch = match name(topic) {
+        Some(name) => {
+            let batch = payload.read(name, &data_type(topic), bytes)?;
+            validate(name, &batch.schema())?;
+            batch
+        }
+        None => {
+            let batch = payload.read(&payload.mapping.value, &DataType::Float64, bytes)?;
+            discover(&batch.schema())?;
+            batch
+        }
+    };
+    Ok(batch)
+}
+
+/// Validates the batch schema against the device topic schema: identifier,
+/// timestamp and numeric value columns
+fn validate(name: &'static str, schema: &Schema) -> Result<(), Mismatch> {
+    let fields = schema.fields();
+    if fields.len() != 3 {
+        return Err(Mismatch::Fields {
+            expected: 3,
+            found: fields.len(),
+        });
+    }
+    let names = [IDENTIFIER, TIMESTAMP, name];
+    for (index, (field, expected)) in zip(fields, names).enumerate() {
+        if !field.name().eq_ignore_ascii_case(expected) {
+            return Err(Mismatch::Name {
+                index,
+                expected,
+                found: field.name().clone(),
+            });
+        }
+    }
+    identifier(&fields[0])?;
+    timestamp(&fields[1])?;
+    if !fields[2].data_type().is_numeric() {
+        return Err(Mismatch::Type {
+            name: fields[2].name().clone(),
+            expected: "numeric",
+            found: fields[2].data_type().clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Validates the batch schema of a discovered topic: identifier and timestamp
+/// columns and at least one numeric value column
+fn discover(schema: &Schema) -> Result<(), Mismatch> {
+    let field = |name| {
+        schema
+            .field_with_name(name)
+            .map_err(|_| Mismatch::Missing { expected: name })
+    };
+    identifier(field(IDENTIFIER)?)?;
+    timestamp(field(TIMESTAMP)?)?;
+    if !schema.fields().iter().any(|field| {
+        field.name() != IDENTIFIER && field.name() != TIMESTAMP && field.data_type().is_numeric()
+    }) {
+        return Err(Mismatch::Missing {
+            expected: "numeric",
+        });
+    }
+    Ok(())
+}
+
+fn identifier(field: &Field) -> Result<(), Mismatch> {
+    if field.data_type() != &DataType::UInt64 {
+        return Err(Mismatch::Type {
+            name: IDENTIFIER.to_owned(),
+            expected: "UInt64",
+            found: field.data_type().clone(),
+        });
+    }
+    Ok(())
+}
+
+fn timestamp(field: &Field) -> Result<(), Mismatch> {
+    if !matches!(
+        field.data_type(),
+        DataType::Timestamp(TimeUnit::Millisecond, _),
+    ) {
+        return Err(Mismatch::Type {
+            name: TIMESTAMP.to_owned(),
+            expected: "Timestamp(Millisecond)",
+            found: field.data_type().clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Value column name of the device topic
+pub(crate) fn name(topic: &str) -> Option<&'static str> {
+    match topic {
+        TOPIC_ATUC => Some(NAME_TURBIDITY),
+        TOPIC_DDOC_C1 => Some(NAME_DDOC_C1),
+        TOPIC_DDOC_C2 => Some(NAME_DDOC_C2),
+        TOPIC_DDOC_T1 => Some(NAME_DDOC_T1),
+        TOPIC_DDOC_T2 => Some(NAME_DDOC_T2),
+        TOPIC_DDOC_V1 => Some(NAME_DDOC_V1),
+        TOPIC_DDOC_V2 => Some(NAME_DDOC_V2),
+        TOPIC_DTEC => Some(NAME_TEMPERATURE),
+        _ => None,
+    }
+}
+
+/// Value column type of the device topic
+fn data_type(topic: &str) -> DataType {
+    match topic {
+        TOPIC_ATUC => DataType::UInt16,
+        _ => DataType::Float32,
+    }
+}
+
+/// Topic buffer stored in the context data
+pub(crate) fn buffer(context: &Context, topic: &str) -> Option<Arc<Mutex<Buffer>>> {
+    context.data(|data| data.get_temp(Id::new(topic)))
+}
+
+/// Clears the topic buffer
+pub(crate) fn clear(context: &Context, topic: &str) {
+    if let Some(buffer) = buffer(context, topic) {
+        buffer.lock().clear();
+        context.request_repaint();
+    }
+}
+
+/// Topics seen under the subscription
+pub(crate) fn topics(context: &Context) -> Arc<Mutex<Topics>> {
+    context.data_mut(|data| {
+        data.get_temp_mut_or_insert_with(Id::new(TOPICS_ID), Default::default)
+            .clone()
+    })
+}
+
+/// Received command acknowledgements, drained by the control panel
+pub(crate) fn acks(context: &Context) -> Arc<Mutex<Vec<Bytes>>> {
+    context.data_mut(|data| {
+        data.get_temp_mut_or_insert_with(Id::new(ACKS), Default::default)
+            .clone()
+    })
+}
+
+/// Staleness watchdog stored in the context data
+pub(crate) fn watchdog(context: &Context) -> Arc<Mutex<Watchdog>> {
+    context.data_mut(|data| {
+        data.get_temp_mut_or_insert_with(Id::new(WATCHDOG), Default::default)
+            .clone()
+    })
+}
+
+/// Applies the staleness settings to the watchdog
+pub(crate) fn watch(context: &Context, staleness: &Staleness) {
+    watchdog(context).lock().configure(staleness.clone());
+}
+
+/// Recorder stored in the context data
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn recorder(context: &Context) -> Arc<Mutex<Recorder>> {
+    context.data_mut(|data| {
+        data.get_temp_mut_or_insert_with(Id::new(RECORDER), Default::default)
+            .clone()
+    })
+}
+
+/// Applies the recording settings to the recorder
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn record(context: &Context, recording: &Recording) {
+    recorder(context).lock().configure(recording.clone());
+}
+
+/// Sets the retention limit applied to the topic buffers
+pub(crate) fn retention(context: &Context, retention: Retention) {
+    context.data_mut(|data| data.insert_temp(Id::new(RETENTION), retention));
+}
+
+fn write(context: &Context, topic: &str, batch: RecordBatch) {
+    let retention = context
+        .data(|data| data.get_temp::<Retention>(Id::new(RETENTION)))
+        .unwrap_or_default();
+    let buffer = context.data_mut(|data| {
+        data.get_temp_mut_or_insert_with(Id::new(topic), || Arc::new(Mutex::new(Buffer::default())))
+            .clone()
+    });
+    buffer.lock().push(batch, retention);
+    context.request_repaint();
+}
+
+mod buffer;
+mod error;
+#[cfg(any(test, target_arch = "wasm32"))]
+mod packet;
+mod payload;
+#[cfg(not(target_arch = "wasm32"))]
+mod recorder;
+mod settings;
+#[cfg(not(target_arch = "wasm32"))]
+mod simulator;
+mod status;
+mod topics;
+mod watchdog;
+#[cfg(target_arch = "wasm32")]
+mod web;
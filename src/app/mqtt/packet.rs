@@ -0,0 +1,392 @@
+//! Minimal MQTT 3.1.1 packet codec for the web client.
+
+use anyhow::{Result, bail, ensure};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+const PROTOCOL_NAME: &str = "MQTT";
+const PROTOCOL_LEVEL: u8 = 4;
+
+const CONNECT: u8 = 1;
+const CONNACK: u8 = 2;
+const PUBLISH: u8 = 3;
+const PUBACK: u8 = 4;
+const PUBREC: u8 = 5;
+const PUBREL: u8 = 6;
+const PUBCOMP: u8 = 7;
+const SUBSCRIBE: u8 = 8;
+const SUBACK: u8 = 9;
+const PINGREQ: u8 = 12;
+const PINGRESP: u8 = 13;
+const DISCONNECT: u8 = 14;
+
+const CLEAN_SESSION: u8 = 0x02;
+const PASSWORD: u8 = 0x40;
+const USERNAME: u8 = 0x80;
+
+/// Incoming packet
+#[derive(Clone, Debug)]
+pub(crate) enum Packet {
+    ConnAck {
+        code: u8,
+    },
+    Publish {
+        qos: u8,
+        packet_id: Option<u16>,
+        topic: String,
+        payload: Bytes,
+    },
+    PubRec {
+        packet_id: u16,
+    },
+    PubRel {
+        packet_id: u16,
+    },
+    SubAck,
+    PingResp,
+    Other(u8),
+}
+
+pub(crate) fn connect(client_id: &str, keep_alive: u16, username: &str, password: &str) -> Vec<u8> {
+    let mut flags = CLEAN_SESSION;
+    let mut body = BytesMut::new();
+    string(&mut body, PROTOCOL_NAME);
+    body.put_u8(PROTOCOL_LEVEL);
+    if !username.is_empty() {
+        flags |= USERNAME;
+        if !password.is_empty() {
+            flags |= PASSWORD;
+        }
+    }
+    body.put_u8(flags);
+    body.put_u16(keep_alive);
+    string(&mut body, client_id);
+    if !username.is_empty() {
+        string(&mut body, username);
+        if !password.is_empty() {
+            string(&mut body, password);
+        }
+    }
+    packet(CONNECT << 4, &body)
+}
+
+pub(crate) fn subscribe(packet_id: u16, topic: &str, qos: u8) -> Vec<u8> {
+    let mut body = BytesMut::new();
+    body.put_u16(packet_id);
+    string(&mut body, topic);
+    body.put_u8(qos);
+    packet(SUBSCRIBE << 4 | 0x02, &body)
+}
+
+pub(crate) fn publish(
+    topic: &str,
+    qos: u8,
+    retain: bool,
+    packet_id: Option<u16>,
+    payload: &[u8],
+) -> Vec<u8> {
+    let mut body = BytesMut::new();
+    string(&mut body, topic);
+    if let Some(packet_id) = packet_id {
+        body.put_u16(packet_id);
+    }
+    body.put_slice(payload);
+    packet(PUBLISH << 4 | qos << 1 | retain as u8, &body)
+}
+
+pub(crate) fn puback(packet_id: u16) -> Vec<u8> {
+    packet(PUBACK << 4, &packet_id.to_be_bytes())
+}
+
+pub(crate) fn pubrec(packet_id: u16) -> Vec<u8> {
+    packet(PUBREC << 4, &packet_id.to_be_bytes())
+}
+
+pub(crate) fn pubrel(packet_id: u16) -> Vec<u8> {
+    packet(PUBREL << 4 | 0x02, &packet_id.to_be_bytes())
+}
+
+pub(crate) fn pubcomp(packet_id: u16) -> Vec<u8> {
+    packet(PUBCOMP << 4, &packet_id.to_be_bytes())
+}
+
+pub(crate) fn pingreq() -> Vec<u8> {
+    packet(PINGREQ << 4, &[])
+}
+
+pub(crate) fn disconnect() -> Vec<u8> {
+    packet(DISCONNECT << 4, &[])
+}
+
+/// Decodes the next complete packet, leaving incomplete data in the buffer
+pub(crate) fn decode(buffer: &mut BytesMut) -> Result<Option<Packet>> {
+    let Some(&header) = buffer.first() else {
+        return Ok(None);
+    };
+    // Remaining length
+    let mut length = 0;
+    let mut index = 1;
+    loop {
+        let Some(&byte) = buffer.get(index) else {
+            return Ok(None);
+        };
+        length |= ((byte & 0x7F) as usize) << (7 * (index - 1));
+        index += 1;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        ensure!(index < 5, "Malformed remaining length");
+    }
+    if buffer.len() < index + length {
+        return Ok(None);
+    }
+    buffer.advance(index);
+    let mut body = buffer.split_to(length).freeze();
+    let packet = match header >> 4 {
+        CONNACK => {
+            ensure!(body.remaining() == 2, "Malformed CONNACK");
+            body.advance(1);
+            Packet::ConnAck {
+                code: body.get_u8(),
+            }
+        }
+        PUBLISH => {
+            let qos = (header >> 1) & 0x03;
+            let length = read_u16(&mut body)? as usize;
+            ensure!(body.remaining() >= length, "Malformed PUBLISH topic");
+            let topic = String::from_utf8(body.split_to(length).to_vec())?;
+            let packet_id = if qos > 0 {
+                Some(read_u16(&mut body)?)
+            } else {
+                None
+            };
+            Packet::Publish {
+                qos,
+                packet_id,
+                topic,
+                payload: body,
+            }
+        }
+        PUBREC => Packet::PubRec {
+            packet_id: read_u16(&mut body)?,
+        },
+        PUBREL => Packet::PubRel {
+            packet_id: read_u16(&mut body)?,
+        },
+        SUBACK => {
+            if body.get(2..).is_some_and(|codes| codes.contains(&0x80)) {
+                bail!("Subscription refused");
+            }
+            Packet::SubAck
+        }
+        PINGRESP => Packet::PingResp,
+        other => Packet::Other(other),
+    };
+    Ok(Some(packet))
+}
+
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(body.len() + 5);
+    bytes.put_u8(header);
+    // Remaining length
+    let mut length = body.len();
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        bytes.put_u8(byte);
+        if length == 0 {
+            break;
+        }
+    }
+    bytes.put_slice(body);
+    bytes
+}
+
+fn string(body: &mut BytesMut, value: &str) {
+    body.put_u16(value.len() as u16);
+    body.put_slice(value.as_bytes());
+}
+
+fn read_u16(body: &mut Bytes) -> Result<u16> {
+    ensure!(body.remaining() >= 2, "Unexpected end of packet");
+    Ok(body.get_u16())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode_all(bytes: &[u8]) -> Result<Option<Packet>> {
+        let mut buffer = BytesMut::from(bytes);
+        let packet = decode(&mut buffer)?;
+        if packet.is_some() {
+            assert!(buffer.is_empty());
+        }
+        Ok(packet)
+    }
+
+    #[test]
+    fn remaining_length() {
+        for (length, bytes) in [(0, 1), (127, 1), (128, 2), (16_383, 2), (16_384, 3)] {
+            let encoded = packet(PINGRESP << 4, &vec![0; length]);
+            assert_eq!(encoded.len(), 1 + bytes + length);
+            assert!(matches!(decode_all(&encoded), Ok(Some(Packet::PingResp))));
+        }
+    }
+
+    #[test]
+    fn incomplete() {
+        let encoded = publish("topic", 0, false, None, b"payload");
+        for end in 0..encoded.len() {
+            let mut buffer = BytesMut::from(&encoded[..end]);
+            assert!(matches!(decode(&mut buffer), Ok(None)));
+            assert_eq!(buffer.len(), end);
+        }
+    }
+
+    #[test]
+    fn sequential() {
+        let mut buffer = BytesMut::from(&pingreq()[..]);
+        buffer.extend_from_slice(&puback(1));
+        buffer.extend_from_slice(&disconnect());
+        assert!(matches!(
+            decode(&mut buffer),
+            Ok(Some(Packet::Other(PINGREQ)))
+        ));
+        assert!(matches!(
+            decode(&mut buffer),
+            Ok(Some(Packet::Other(PUBACK)))
+        ));
+        assert!(matches!(
+            decode(&mut buffer),
+            Ok(Some(Packet::Other(DISCONNECT)))
+        ));
+        assert!(matches!(decode(&mut buffer), Ok(None)));
+    }
+
+    #[test]
+    fn connect_credentials() {
+        let encoded = connect("id", 60, "user", "pass");
+        let mut expected = vec![CONNECT << 4, 26, 0, 4];
+        expected.extend_from_slice(b"MQTT");
+        expected.extend_from_slice(&[PROTOCOL_LEVEL, CLEAN_SESSION | USERNAME | PASSWORD, 0, 60]);
+        expected.extend_from_slice(&[0, 2]);
+        expected.extend_from_slice(b"id");
+        expected.extend_from_slice(&[0, 4]);
+        expected.extend_from_slice(b"user");
+        expected.extend_from_slice(&[0, 4]);
+        expected.extend_from_slice(b"pass");
+        assert_eq!(encoded, expected);
+        assert!(matches!(
+            decode_all(&encoded),
+            Ok(Some(Packet::Other(CONNECT)))
+        ));
+    }
+
+    #[test]
+    fn connect_anonymous() {
+        // A password without a username is not sent
+        let encoded = connect("id", 60, "", "pass");
+        let mut expected = vec![CONNECT << 4, 14, 0, 4];
+        expected.extend_from_slice(b"MQTT");
+        expected.extend_from_slice(&[PROTOCOL_LEVEL, CLEAN_SESSION, 0, 60]);
+        expected.extend_from_slice(&[0, 2]);
+        expected.extend_from_slice(b"id");
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn subscribe_topic() {
+        let encoded = subscribe(1, "a/#", 2);
+        let mut expected = vec![SUBSCRIBE << 4 | 0x02, 8, 0, 1, 0, 3];
+        expected.extend_from_slice(b"a/#");
+        expected.push(2);
+        assert_eq!(encoded, expected);
+        assert!(matches!(
+            decode_all(&encoded),
+            Ok(Some(Packet::Other(SUBSCRIBE)))
+        ));
+    }
+
+    #[test]
+    fn publish_at_most_once() {
+        let encoded = publish("a/b", 0, false, None, b"42");
+        assert_eq!(encoded[0], PUBLISH << 4);
+        let Ok(Some(Packet::Publish {
+            qos,
+            packet_id,
+            topic,
+            payload,
+        })) = decode_all(&encoded)
+        else {
+            panic!("expected PUBLISH");
+        };
+        assert_eq!(qos, 0);
+        assert_eq!(packet_id, None);
+        assert_eq!(topic, "a/b");
+        assert_eq!(&payload[..], b"42");
+    }
+
+    #[test]
+    fn publish_at_least_once() {
+        let encoded = publish("a/b", 1, true, Some(7), b"42");
+        assert_eq!(encoded[0], PUBLISH << 4 | 1 << 1 | 1);
+        let Ok(Some(Packet::Publish {
+            qos,
+            packet_id,
+            topic,
+            payload,
+        })) = decode_all(&encoded)
+        else {
+            panic!("expected PUBLISH");
+        };
+        assert_eq!(qos, 1);
+        assert_eq!(packet_id, Some(7));
+        assert_eq!(topic, "a/b");
+        assert_eq!(&payload[..], b"42");
+    }
+
+    #[test]
+    fn acknowledgements() {
+        assert!(matches!(
+            decode_all(&[CONNACK << 4, 2, 0, 5]),
+            Ok(Some(Packet::ConnAck { code: 5 }))
+        ));
+        assert!(matches!(
+            decode_all(&pubrel(9)),
+            Ok(Some(Packet::PubRel { packet_id: 9 }))
+        ));
+        assert!(matches!(
+            decode_all(&pubrec(9)),
+            Ok(Some(Packet::PubRec { packet_id: 9 }))
+        ));
+        assert!(matches!(
+            decode_all(&pubcomp(9)),
+            Ok(Some(Packet::Other(PUBCOMP)))
+        ));
+        assert!(matches!(
+            decode_all(&[SUBACK << 4, 3, 0, 1, 2]),
+            Ok(Some(Packet::SubAck))
+        ));
+    }
+
+    #[test]
+    fn malformed() {
+        // Remaining length over four bytes
+        assert!(decode_all(&[PINGRESP << 4, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]).is_err());
+        // CONNACK of a wrong size
+        assert!(decode_all(&[CONNACK << 4, 1, 0]).is_err());
+        // Topic longer than the packet
+        assert!(decode_all(&[PUBLISH << 4, 2, 0, 5]).is_err());
+        // Topic not in UTF-8
+        assert!(decode_all(&[PUBLISH << 4, 3, 0, 1, 0xFF]).is_err());
+        // Packet identifier missing with QoS 1
+        assert!(decode_all(&[PUBLISH << 4 | 1 << 1, 3, 0, 1, b'a']).is_err());
+        // Acknowledgement without a packet identifier
+        assert!(decode_all(&[PUBREC << 4, 1, 0]).is_err());
+        // Refused subscription
+        assert!(decode_all(&[SUBACK << 4, 3, 0, 1, 0x80]).is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use super::{Error, IDENTIFIER, TIMESTAMP};
+use arrow::{
+    array::RecordBatch,
+    compute::{cast, concat_batches},
+    datatypes::{DataType, Field, Schema, TimeUnit},
+    ipc::reader::StreamReader,
+    json::ReaderBuilder,
+};
+use bytes::{Bytes, buf::Buf};
+use egui::{ComboBox, TextEdit, Ui};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{Deserializer, Value};
+use std::sync::Arc;
+
+const VALUE: &str = "Value";
+
+/// Payload settings of a topic
+#[derive(Clone, Debug, Default, Deserialize, Hash, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Payload {
+    pub(crate) format: Format,
+    pub(crate) mapping: Mapping,
+}
+
+impl Payload {
+    /// Decodes the payload into the topic schema with the given value column
+    /// name and type
+    pub(super) fn read(
+        &self,
+        name: &str,
+        data_type: &DataType,
+        bytes: Bytes,
+    ) -> Result<RecordBatch, Error> {
+        match self.format.sniff(&bytes) {
+            Format::Json => json(&self.mapping, name, data_type, &bytes),
+            _ => ipc(bytes),
+        }
+    }
+
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        ComboBox::from_id_salt(ui.next_auto_id())
+            .selected_text(ui.localize(self.format.text()))
+            .show_ui(ui, |ui| {
+                for format in [Format::Auto, Format::Ipc, Format::Json] {
+                    ui.selectable_value(&mut self.format, format, ui.localize(format.text()))
+                        .on_hover_localized(format.hover_text());
+                }
+            })
+            .response
+            .on_hover_localized(self.format.hover_text());
+        let enabled = self.format != Format::Ipc;
+        for (text, hint_text) in [
+            (&mut self.mapping.identifier, IDENTIFIER),
+            (&mut self.mapping.timestamp, TIMESTAMP),
+            (&mut self.mapping.value, VALUE),
+        ] {
+            ui.add_enabled(
+                enabled,
+                TextEdit::singleline(text)
+                    .hint_text(hint_text)
+                    .desired_width(96.0),
+            );
+        }
+    }
+}
+
+/// Payload format
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub(crate) enum Format {
+    /// Sniffed from the content
+    #[default]
+    Auto,
+    /// Arrow IPC stream
+    Ipc,
+    /// JSON object, array of objects or newline delimited objects
+    Json,
+}
+
+impl Format {
+    /// Resolves the automatic format from the content: JSON starts with an
+    /// object or an array, anything else is read as an Arrow IPC stream
+    pub(crate) fn sniff(self, bytes: &[u8]) -> Self {
+        match self {
+            Self::Auto => match first(bytes) {
+                Some(b'{' | b'[') => Self::Json,
+                _ => Self::Ipc,
+            },
+            format => format,
+        }
+    }
+
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::Auto => "format__auto",
+            Self::Ipc => "format__ipc",
+            Self::Json => "format__json",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::Auto => "format__auto.hover",
+            Self::Ipc => "format__ipc.hover",
+            Self::Json => "format__json.hover",
+        }
+    }
+}
+
+/// JSON field names of the identifier, timestamp and value columns
+#[derive(Clone, Debug, Deserialize, Hash, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Mapping {
+    pub(crate) identifier: String,
+    pub(crate) timestamp: String,
+    pub(crate) value: String,
+}
+
+impl Mapping {
+    pub(crate) fn new() -> Self {
+        Self {
+            identifier: IDENTIFIER.to_owned(),
+            timestamp: TIMESTAMP.to_owned(),
+            value: VALUE.to_owned(),
+        }
+    }
+}
+
+impl Default for Mapping {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads all batches of the Arrow IPC stream into one batch
+fn ipc(bytes: Bytes) -> Result<RecordBatch, Error> {
+    let projection = None; // read all columns
+    let reader = StreamReader::try_new(bytes.reader(), projection)?;
+    let schema = reader.schema();
+    let batches = reader.collect::<Result<Vec<_>, _>>()?;
+    Ok(concat_batches(&schema, &batches)?)
+}
+
+/// Reads the mapped JSON fields into the topic schema
+///
+/// Timestamps are either milliseconds since the epoch or RFC 3339 strings.
+/// Values are read as floats and cast to the value type, so the JSON and the
+/// Arrow payloads of a topic share the schema.
+fn json(
+    mapping: &Mapping,
+    name: &str,
+    data_type: &DataType,
+    bytes: &[u8],
+) -> Result<RecordBatch, Error> {
+    let values = if first(bytes) == Some(b'[') {
+        serde_json::from_slice::<Vec<Value>>(bytes)?
+    } else {
+        Deserializer::from_slice(bytes)
+            .into_iter::<Value>()
+            .collect::<Result<_, _>>()?
+    };
+    let fields = |identifier: &str, timestamp: &str, value: &str, data_type: &DataType| {
+        Arc::new(Schema::new(vec![
+            Field::new(identifier, DataType::UInt64, false),
+            Field::new(
+                timestamp,
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new(value, data_type.clone(), true),
+        ]))
+    };
+    let source = fields(
+        &mapping.identifier,
+        &mapping.timestamp,
+        &mapping.value,
+        &DataType::Float64,
+    );
+    let mut decoder = ReaderBuilder::new(source.clone()).build_decoder()?;
+    decoder.serialize(&values)?;
+    let batch = decoder
+        .flush()?
+        .unwrap_or_else(|| RecordBatch::new_empty(source));
+    let target = fields(IDENTIFIER, TIMESTAMP, name, data_type);
+    let columns = vec![
+        batch.column(0).clone(),
+        batch.column(1).clone(),
+        cast(batch.column(2), data_type)?,
+    ];
+    Ok(RecordBatch::try_new(target, columns)?)
+}
+
+/// First non whitespace byte
+fn first(bytes: &[u8]) -> Option<u8> {
+    bytes
+        .iter()
+        .copied()
+        .find(|byte| !byte.is_ascii_whitespace())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use arrow::array::{
+        AsArray as _,
+        types::{Float64Type, TimestampMillisecondType, UInt16Type, UInt64Type},
+    };
+
+    const IPC: &[u8] = b"\xFF\xFF\xFF\xFF";
+
+    fn payload(format: Format, identifier: &str, timestamp: &str, value: &str) -> Payload {
+        Payload {
+            format,
+            mapping: Mapping {
+                identifier: identifier.to_owned(),
+                timestamp: timestamp.to_owned(),
+                value: value.to_owned(),
+            },
+        }
+    }
+
+    #[test]
+    fn sniff() {
+        assert_eq!(Format::Auto.sniff(b" \n{}"), Format::Json);
+        assert_eq!(Format::Auto.sniff(b"\t[]"), Format::Json);
+        assert_eq!(Format::Auto.sniff(IPC), Format::Ipc);
+        assert_eq!(Format::Auto.sniff(b""), Format::Ipc);
+        // Explicit formats aren't sniffed
+        assert_eq!(Format::Ipc.sniff(b"{}"), Format::Ipc);
+        assert_eq!(Format::Json.sniff(IPC), Format::Json);
+    }
+
+    #[test]
+    fn object() -> Result<(), Error> {
+        let bytes = Bytes::from_static(br#"{"Identifier": 1, "Timestamp": 2, "Value": 3.5}"#);
+        let batch = Payload::default().read("Temperature", &DataType::Float64, bytes)?;
+        let schema = batch.schema();
+        let names = schema.fields().iter().map(|field| field.name().as_str());
+        assert!(names.eq([IDENTIFIER, TIMESTAMP, "Temperature"]));
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(batch.column(2).as_primitive::<Float64Type>().value(0), 3.5);
+        Ok(())
+    }
+
+    #[test]
+    fn mapping() -> Result<(), Error> {
+        let bytes = Bytes::from_static(
+            br#"[{"id": 1, "time": "2025-01-01T00:00:00Z", "v": 1.5}, {"id": 2, "time": 1000, "v": 2}]"#,
+        );
+        let batch =
+            payload(Format::Json, "id", "time", "v").read("Value", &DataType::Float64, bytes)?;
+        assert_eq!(batch.num_rows(), 2);
+        let identifiers = batch.column(0).as_primitive::<UInt64Type>();
+        assert_eq!(identifiers.values(), &[1, 2]);
+        let timestamps = batch.column(1).as_primitive::<TimestampMillisecondType>();
+        assert_eq!(timestamps.values(), &[1_735_689_600_000, 1000]);
+        Ok(())
+    }
+
+    #[test]
+    fn ndjson() -> Result<(), Error> {
+        let bytes = Bytes::from_static(
+            b"{\"Identifier\": 1, \"Timestamp\": 1, \"Value\": 1}\n{\"Identifier\": 1, \"Timestamp\": 2, \"Value\": 2}\n",
+        );
+        let batch = Payload::default().read("Value", &DataType::Float64, bytes)?;
+        assert_eq!(batch.num_rows(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn data_type() -> Result<(), Error> {
+        let bytes = Bytes::from_static(br#"{"Identifier": 1, "Timestamp": 2, "Value": 300}"#);
+        let batch = Payload::default().read("Turbidity", &DataType::UInt16, bytes)?;
+        assert_eq!(batch.schema().field(2).data_type(), &DataType::UInt16);
+        assert_eq!(batch.column(2).as_primitive::<UInt16Type>().value(0), 300);
+        Ok(())
+    }
+
+    #[test]
+    fn ipc_round_trip() -> Result<(), Error> {
+        use arrow::{
+            array::{Float32Array, TimestampMillisecondArray, UInt64Array},
+            ipc::writer::StreamWriter,
+        };
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(IDENTIFIER, DataType::UInt64, false),
+            Field::new(
+                TIMESTAMP,
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new("Temperature", DataType::Float32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt64Array::from(vec![1])),
+                Arc::new(TimestampMillisecondArray::from(vec![2])),
+                Arc::new(Float32Array::from(vec![3.0])),
+            ],
+        )?;
+        let mut writer = StreamWriter::try_new(Vec::new(), &schema)?;
+        writer.write(&batch)?;
+        let bytes = Bytes::from(writer.into_inner()?);
+        let read = Payload::default().read("Temperature", &DataType::Float32, bytes)?;
+        assert_eq!(read, batch);
+        Ok(())
+    }
+
+    #[test]
+    fn malformed() {
+        let read = |bytes: &'static [u8]| {
+            Payload::default().read("Value", &DataType::Float64, Bytes::from_static(bytes))
+        };
+        assert!(read(b"{").is_err());
+        assert!(read(br#"{"Identifier": "one", "Timestamp": 1, "Value": 1}"#).is_err());
+        assert!(read(b"garbage").is_err());
+    }
+}
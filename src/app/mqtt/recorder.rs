@@ -0,0 +1,353 @@
+use super::{IDENTIFIER, TIMESTAMP, TOPICS, topics};
+use crate::app::{
+    YMDHMS,
+    metadata::{MAX_TIMESTAMP, MIN_TIMESTAMP, NAME},
+};
+use anyhow::{Context as _, Result, bail, ensure};
+use arrow::{
+    array::{AsArray as _, RecordBatch},
+    compute::{max, min},
+    datatypes::{SchemaRef, TimestampMillisecondType},
+    temporal_conversions::timestamp_ms_to_datetime,
+};
+use chrono::{DateTime, Utc};
+use egui::{ComboBox, Grid, RichText, Ui};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::RECORD;
+use parquet::{arrow::ArrowWriter, file::metadata::KeyValue};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap, hash_map::Entry},
+    fs::{self, create_dir_all},
+    path::{Component, Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tracing::{error, info, instrument};
+
+const DIRECTORY: &str = "records";
+const FILE_NAME: &str = "%Y-%m-%d_%H-%M-%S";
+/// Rows and time after which the buffered rows are written as a row group
+const FLUSH_ROWS: usize = 1024;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Recording settings
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Recording {
+    /// Directory of the recorded files
+    pub(crate) directory: String,
+    pub(crate) rotation: Rotation,
+    /// Recorded topics relative to the prefix
+    pub(crate) topics: BTreeSet<String>,
+}
+
+impl Recording {
+    pub(crate) fn new() -> Self {
+        Self {
+            directory: DIRECTORY.to_owned(),
+            rotation: Rotation::Day,
+            topics: BTreeSet::new(),
+        }
+    }
+}
+
+impl Recording {
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        let recorder = super::recorder(ui.ctx());
+        let recorder = recorder.lock();
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            // Directory
+            ui.label(ui.localize("recording__directory"))
+                .on_hover_localized("recording__directory.hover");
+            ui.text_edit_singleline(&mut self.directory);
+            ui.end_row();
+            // Rotation
+            ui.label(ui.localize("recording__rotation"))
+                .on_hover_localized("recording__rotation.hover");
+            ComboBox::from_id_salt("Rotation")
+                .selected_text(ui.localize(self.rotation.text()))
+                .show_ui(ui, |ui| {
+                    for rotation in [Rotation::Hour, Rotation::Day] {
+                        ui.selectable_value(
+                            &mut self.rotation,
+                            rotation,
+                            ui.localize(rotation.text()),
+                        )
+                        .on_hover_localized(rotation.hover_text());
+                    }
+                })
+                .response
+                .on_hover_localized(self.rotation.hover_text());
+            ui.end_row();
+            // Last error
+            if let Some(error) = &recorder.error {
+                ui.label(ui.localize("mqtt__last_error"));
+                ui.label(error);
+                ui.end_row();
+            }
+        });
+        ui.separator();
+        // Topics
+        let mut names = TOPICS.map(ToOwned::to_owned).to_vec();
+        for (topic, _) in topics(ui.ctx()).lock().iter() {
+            if !names.contains(topic) {
+                names.push(topic.clone());
+            }
+        }
+        for topic in names {
+            let mut checked = self.topics.contains(&topic);
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut checked, &topic).changed() {
+                    if checked {
+                        self.topics.insert(topic.clone());
+                    } else {
+                        self.topics.remove(&topic);
+                    }
+                }
+                if let Some(file) = recorder.files.get(&topic) {
+                    ui.label(RichText::new(RECORD).color(ui.visuals().error_fg_color))
+                        .on_hover_text(file.path.display().to_string());
+                }
+            });
+        }
+    }
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// File rotation period
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Rotation {
+    Hour,
+    #[default]
+    Day,
+}
+
+impl Rotation {
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::Hour => "rotation__hour",
+            Self::Day => "rotation__day",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::Hour => "rotation__hour.hover",
+            Self::Day => "rotation__day.hover",
+        }
+    }
+
+    /// Period of the time, files are rotated when it changes
+    fn period(self, time: DateTime<Utc>) -> String {
+        match self {
+            Self::Hour => time.format("%Y-%m-%d %H").to_string(),
+            Self::Day => time.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// Recorder
+///
+/// Writes the batches of the recorded topics to rotating parquet files in the
+/// local directory.
+#[derive(Default)]
+pub(crate) struct Recorder {
+    recording: Option<Recording>,
+    files: HashMap<String, File>,
+    pub(crate) error: Option<String>,
+}
+
+impl Recorder {
+    /// Applies the recording settings, closing the files of the topics which
+    /// are no longer recorded
+    pub(crate) fn configure(&mut self, recording: Recording) {
+        if self.recording.as_ref().is_some_and(|current| {
+            current.directory != recording.directory || current.rotation != recording.rotation
+        }) {
+            self.close_all();
+        }
+        let stopped = self
+            .files
+            .keys()
+            .filter(|topic| !recording.topics.contains(*topic))
+            .cloned()
+            .collect::<Vec<_>>();
+        for topic in stopped {
+            self.close(&topic);
+        }
+        self.recording = Some(recording);
+    }
+
+    pub(crate) fn write(&mut self, topic: &str, batch: &RecordBatch) {
+        if let Err(error) = self.try_write(topic, batch) {
+            error!(%error);
+            self.error = Some(error.to_string());
+        }
+    }
+
+    /// Closes all files, writing their footers
+    pub(crate) fn close_all(&mut self) {
+        let topics = self.files.keys().cloned().collect::<Vec<_>>();
+        for topic in topics {
+            self.close(&topic);
+        }
+    }
+
+    fn try_write(&mut self, topic: &str, batch: &RecordBatch) -> Result<()> {
+        let Some(recording) = &self.recording else {
+            return Ok(());
+        };
+        if !recording.topics.contains(topic) {
+            return Ok(());
+        }
+        if recording.directory.is_empty() {
+            bail!("Recording directory is not set");
+        }
+        let directory = PathBuf::from(&recording.directory);
+        let now = Utc::now();
+        let period = recording.rotation.period(now);
+        // Rotate on the period or the schema change
+        if self
+            .files
+            .get(topic)
+            .is_some_and(|file| file.period != period || file.schema != batch.schema())
+        {
+            self.close(topic);
+        }
+        let file = match self.files.entry(topic.to_owned()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let path = directory
+                    .join(relative(topic)?)
+                    .join(format!("{}.parquet", now.format(FILE_NAME)));
+                entry.insert(File::new(path, period, batch.schema())?)
+            }
+        };
+        file.write(batch)
+    }
+
+    fn close(&mut self, topic: &str) {
+        if let Some(file) = self.files.remove(topic) {
+            if let Err(error) = file.close() {
+                error!(%error);
+                self.error = Some(error.to_string());
+            }
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.close_all();
+    }
+}
+
+/// Recorded file
+struct File {
+    path: PathBuf,
+    period: String,
+    schema: SchemaRef,
+    writer: ArrowWriter<fs::File>,
+    min: Option<i64>,
+    max: Option<i64>,
+    /// Last written row group
+    flushed: Instant,
+}
+
+impl File {
+    #[instrument(skip(schema), err)]
+    fn new(path: PathBuf, period: String, schema: SchemaRef) -> Result<Self> {
+        info!("Start recording");
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let writer = ArrowWriter::try_new(fs::File::create(&path)?, schema.clone(), None)?;
+        Ok(Self {
+            path,
+            period,
+            schema,
+            writer,
+            min: None,
+            max: None,
+            flushed: Instant::now(),
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.writer.write(batch)?;
+        // Bounds the rows kept in memory and lost on a crash
+        if self.writer.in_progress_rows() >= FLUSH_ROWS || self.flushed.elapsed() >= FLUSH_INTERVAL
+        {
+            self.writer.flush()?;
+            self.flushed = Instant::now();
+        }
+        if let Some(timestamp) = batch.column_by_name(TIMESTAMP) {
+            let timestamp = timestamp
+                .as_primitive_opt::<TimestampMillisecondType>()
+                .context("Timestamp is not in milliseconds")?;
+            if let Some(value) = min(timestamp) {
+                self.min = Some(self.min.map_or(value, |min| min.min(value)));
+            }
+            if let Some(value) = max(timestamp) {
+                self.max = Some(self.max.map_or(value, |max| max.max(value)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the metadata read back by the drag and drop loading and the
+    /// footer
+    #[instrument(skip(self), fields(path = %self.path.display()), err)]
+    fn close(mut self) -> Result<()> {
+        // Value columns as the loading names them
+        let names = self
+            .schema
+            .fields()
+            .iter()
+            .filter(|field| {
+                field.name() != IDENTIFIER
+                    && field.name() != TIMESTAMP
+                    && field.data_type().is_numeric()
+            })
+            .map(|field| field.name().to_lowercase())
+            .collect::<Vec<_>>();
+        if !names.is_empty() {
+            self.writer
+                .append_key_value_metadata(KeyValue::new(NAME.to_owned(), names.join(",")));
+        }
+        for (key, timestamp) in [(MIN_TIMESTAMP, self.min), (MAX_TIMESTAMP, self.max)] {
+            if let Some(timestamp) = timestamp.and_then(timestamp_ms_to_datetime) {
+                self.writer.append_key_value_metadata(KeyValue::new(
+                    key.to_owned(),
+                    timestamp.format(YMDHMS).to_string(),
+                ));
+            }
+        }
+        self.writer.close()?;
+        info!("Stop recording");
+        Ok(())
+    }
+}
+
+/// Relative path of the topic, the topic comes from the broker and must not
+/// leave the recording directory
+fn relative(topic: &str) -> Result<PathBuf> {
+    let mut path = PathBuf::new();
+    for segment in topic.split('/') {
+        let mut components = Path::new(segment).components();
+        ensure!(
+            matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(_)), None)
+            ),
+            "Topic {topic} is not a relative path"
+        );
+        path.push(segment);
+    }
+    Ok(path)
+}
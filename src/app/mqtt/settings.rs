@@ -0,0 +1,268 @@
+use super::{IDENTIFIER, Payload, TIMESTAMP, TOPICS, topics};
+use chrono::Utc;
+use egui::{ComboBox, DragValue, Grid, TextEdit, Ui, util::hash};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::LazyLock,
+};
+
+const ID: &str = "ippras.ru/blcs/viewer";
+const HOST: &str = "broker.emqx.io";
+const PORT: u16 = 1883;
+const WEBSOCKET_PORT: u16 = 8084;
+const WEBSOCKET_PATH: &str = "/mqtt";
+const PREFIX: &str = "ippras.ru/blcs";
+const KEEP_ALIVE: u64 = 9;
+
+/// Unique suffix of the generated client identifier, one per running instance
+static INSTANCE: LazyLock<u64> = LazyLock::new(|| hash(Utc::now().timestamp_nanos_opt()));
+
+/// MQTT settings
+#[derive(Clone, Debug, Deserialize, Hash, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Settings {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    /// WebSocket port, used by the web client
+    pub(crate) websocket_port: u16,
+    /// WebSocket path, used by the web client
+    pub(crate) websocket_path: String,
+    pub(crate) username: String,
+    /// Not persisted, the storage isn't encrypted
+    #[serde(skip)]
+    pub(crate) password: String,
+    pub(crate) tls: bool,
+    /// Path to the PEM encoded CA certificate, the platform roots are used if
+    /// empty
+    pub(crate) ca: String,
+    /// Client identifier, a unique one is generated for each instance if empty
+    pub(crate) client_id: String,
+    /// Keep alive interval, seconds
+    pub(crate) keep_alive: u64,
+    pub(crate) qos: Qos,
+    pub(crate) prefix: String,
+    /// Payload settings by topic relative to the prefix
+    pub(crate) payloads: BTreeMap<String, Payload>,
+}
+
+impl Settings {
+    pub(crate) fn new() -> Self {
+        Self {
+            host: HOST.to_owned(),
+            port: PORT,
+            websocket_port: WEBSOCKET_PORT,
+            websocket_path: WEBSOCKET_PATH.to_owned(),
+            username: String::new(),
+            password: String::new(),
+            // Browsers served over HTTPS refuse insecure WebSockets
+            tls: cfg!(target_arch = "wasm32"),
+            ca: String::new(),
+            client_id: String::new(),
+            keep_alive: KEEP_ALIVE,
+            qos: Qos::ExactlyOnce,
+            prefix: PREFIX.to_owned(),
+            payloads: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn client_id(&self) -> String {
+        if self.client_id.is_empty() {
+            format!("{ID}/{:016x}", *INSTANCE)
+        } else {
+            self.client_id.clone()
+        }
+    }
+
+    /// WebSocket URL
+    pub(crate) fn url(&self) -> String {
+        let scheme = if self.tls { "wss" } else { "ws" };
+        format!(
+            "{scheme}://{}:{}{}",
+            self.host, self.websocket_port, self.websocket_path,
+        )
+    }
+
+    /// Absolute topic name
+    pub(crate) fn topic(&self, topic: &str) -> String {
+        if self.prefix.is_empty() {
+            topic.to_owned()
+        } else {
+            format!("{}/{topic}", self.prefix)
+        }
+    }
+
+    /// Topic name relative to the prefix
+    pub(crate) fn strip<'a>(&self, topic: &'a str) -> Option<&'a str> {
+        if self.prefix.is_empty() {
+            Some(topic)
+        } else {
+            topic.strip_prefix(&*self.prefix)?.strip_prefix('/')
+        }
+    }
+}
+
+impl Settings {
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            // Host
+            ui.label(ui.localize("mqtt__host"))
+                .on_hover_localized("mqtt__host.hover");
+            ui.text_edit_singleline(&mut self.host);
+            ui.end_row();
+            // Port
+            ui.label(ui.localize("mqtt__port"))
+                .on_hover_localized("mqtt__port.hover");
+            ui.add(DragValue::new(&mut self.port).range(1..=u16::MAX));
+            ui.end_row();
+            // WebSocket
+            ui.label(ui.localize("mqtt__websocket"))
+                .on_hover_localized("mqtt__websocket.hover");
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(&mut self.websocket_port).range(1..=u16::MAX));
+                ui.text_edit_singleline(&mut self.websocket_path);
+            })
+            .response
+            .on_hover_text(self.url());
+            ui.end_row();
+            // Username
+            ui.label(ui.localize("mqtt__username"))
+                .on_hover_localized("mqtt__username.hover");
+            ui.text_edit_singleline(&mut self.username);
+            ui.end_row();
+            // Password
+            ui.label(ui.localize("mqtt__password"))
+                .on_hover_localized("mqtt__password.hover");
+            ui.add(TextEdit::singleline(&mut self.password).password(true));
+            ui.end_row();
+            // TLS
+            ui.label(ui.localize("mqtt__tls"))
+                .on_hover_localized("mqtt__tls.hover");
+            ui.checkbox(&mut self.tls, "");
+            ui.end_row();
+            // CA
+            ui.label(ui.localize("mqtt__ca"))
+                .on_hover_localized("mqtt__ca.hover");
+            ui.add_enabled(self.tls, TextEdit::singleline(&mut self.ca));
+            ui.end_row();
+            // Client identifier
+            ui.label(ui.localize("mqtt__client_id"))
+                .on_hover_localized("mqtt__client_id.hover");
+            let hint_text = self.client_id();
+            ui.add(TextEdit::singleline(&mut self.client_id).hint_text(hint_text));
+            ui.end_row();
+            // Keep alive
+            ui.label(ui.localize("mqtt__keep_alive"))
+                .on_hover_localized("mqtt__keep_alive.hover");
+            ui.add(DragValue::new(&mut self.keep_alive).range(1..=u16::MAX as u64));
+            ui.end_row();
+            // QoS
+            ui.label(ui.localize("mqtt__qos"))
+                .on_hover_localized("mqtt__qos.hover");
+            ComboBox::from_id_salt("Qos")
+                .selected_text(ui.localize(self.qos.text()))
+                .show_ui(ui, |ui| {
+                    for qos in [Qos::AtMostOnce, Qos::AtLeastOnce, Qos::ExactlyOnce] {
+                        ui.selectable_value(&mut self.qos, qos, ui.localize(qos.text()))
+                            .on_hover_localized(qos.hover_text());
+                    }
+                })
+                .response
+                .on_hover_localized(self.qos.hover_text());
+            ui.end_row();
+            // Prefix
+            ui.label(ui.localize("mqtt__prefix"))
+                .on_hover_localized("mqtt__prefix.hover");
+            ui.text_edit_singleline(&mut self.prefix);
+            ui.end_row();
+        });
+        // Payloads
+        ui.collapsing(ui.localize("mqtt__payloads"), |ui| {
+            Grid::new(ui.next_auto_id()).show(ui, |ui| {
+                ui.label(ui.localize("mqtt__topic"));
+                ui.label(ui.localize("mqtt__format"));
+                ui.label(IDENTIFIER);
+                ui.label(TIMESTAMP);
+                ui.label(ui.localize("mqtt__value"));
+                ui.end_row();
+                // Discovered and previously mapped topics after the device ones
+                let mut others: BTreeSet<String> = topics(ui.ctx())
+                    .lock()
+                    .iter()
+                    .map(|(topic, _)| topic.clone())
+                    .collect();
+                others.extend(self.payloads.keys().cloned());
+                others.retain(|topic| !TOPICS.contains(&topic.as_str()));
+                for topic in TOPICS.map(str::to_owned).into_iter().chain(others) {
+                    ui.label(&topic);
+                    // Defaults aren't stored until edited
+                    let previous = self.payloads.get(&topic).cloned().unwrap_or_default();
+                    let mut payload = previous.clone();
+                    payload.show(ui);
+                    if payload != previous {
+                        self.payloads.insert(topic, payload);
+                    }
+                    ui.end_row();
+                }
+            });
+        })
+        .header_response
+        .on_hover_localized("mqtt__payloads.hover");
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Quality of service
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub(crate) enum Qos {
+    AtMostOnce,
+    AtLeastOnce,
+    #[default]
+    ExactlyOnce,
+}
+
+impl Qos {
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::AtMostOnce => "qos__at_most_once",
+            Self::AtLeastOnce => "qos__at_least_once",
+            Self::ExactlyOnce => "qos__exactly_once",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::AtMostOnce => "qos__at_most_once.hover",
+            Self::AtLeastOnce => "qos__at_least_once.hover",
+            Self::ExactlyOnce => "qos__exactly_once.hover",
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<Qos> for rumqttc::QoS {
+    fn from(value: Qos) -> Self {
+        match value {
+            Qos::AtMostOnce => Self::AtMostOnce,
+            Qos::AtLeastOnce => Self::AtLeastOnce,
+            Qos::ExactlyOnce => Self::ExactlyOnce,
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl From<Qos> for u8 {
+    fn from(value: Qos) -> Self {
+        match value {
+            Qos::AtMostOnce => 0,
+            Qos::AtLeastOnce => 1,
+            Qos::ExactlyOnce => 2,
+        }
+    }
+}
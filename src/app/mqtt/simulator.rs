@@ -0,0 +1,365 @@
+use super::{
+    IDENTIFIER, Settings, TIMESTAMP, TOPIC_ATUC, TOPIC_DDOC_C1, TOPIC_DDOC_C2, TOPIC_DDOC_T1,
+    TOPIC_DDOC_T2, TOPIC_DDOC_V1, TOPIC_DDOC_V2, TOPIC_DTEC, TOPICS, name, options,
+};
+use anyhow::Result;
+use arrow::{
+    array::{
+        ArrayRef, Float32Array, RecordBatch, TimestampMillisecondArray, UInt16Array, UInt64Array,
+    },
+    datatypes::{DataType, Field, Schema, TimeUnit},
+    ipc::writer::StreamWriter,
+};
+use chrono::Utc;
+use egui::{DragValue, Grid, Slider, Ui};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::{MINUS, PLUS};
+use rumqttc::Client;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    thread::{sleep, spawn},
+    time::Duration,
+};
+use tracing::{error, info, instrument};
+
+const CAPACITY: usize = 64;
+const MIN_RATE: f64 = 0.1;
+const MILLISECONDS_PER_HOUR: f64 = 3_600_000.0;
+
+/// Simulation settings
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Simulation {
+    /// Identifiers of the simulated devices
+    pub(crate) identifiers: Vec<u64>,
+    /// Samples per second
+    pub(crate) rate: f64,
+    /// Standard deviation of the noise, fraction of the nominal value
+    pub(crate) noise: f64,
+    /// Drift per hour, fraction of the nominal value
+    pub(crate) drift: f64,
+    /// Probability to drop a sample
+    pub(crate) dropout: f64,
+    /// Step change, fraction of the nominal value
+    pub(crate) step: f64,
+    /// Period of the step change, seconds, disabled if zero
+    pub(crate) step_period: u64,
+    /// Simulated topics relative to the prefix
+    pub(crate) topics: BTreeSet<String>,
+}
+
+impl Simulation {
+    pub(crate) fn new() -> Self {
+        Self {
+            identifiers: vec![1, 2],
+            rate: 1.0,
+            noise: 0.01,
+            drift: 0.0,
+            dropout: 0.0,
+            step: 0.1,
+            step_period: 0,
+            topics: TOPICS.map(ToOwned::to_owned).into(),
+        }
+    }
+}
+
+impl Simulation {
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            // Identifiers
+            ui.label(ui.localize("simulation__identifiers"))
+                .on_hover_localized("simulation__identifiers.hover");
+            ui.horizontal_wrapped(|ui| {
+                for identifier in &mut self.identifiers {
+                    ui.add(DragValue::new(identifier).hexadecimal(1, false, false));
+                }
+                if ui.small_button(PLUS).clicked() {
+                    let next = self.identifiers.iter().max().map_or(1, |max| max + 1);
+                    self.identifiers.push(next);
+                }
+                if ui.small_button(MINUS).clicked() {
+                    self.identifiers.pop();
+                }
+            });
+            ui.end_row();
+            // Rate
+            ui.label(ui.localize("simulation__rate"))
+                .on_hover_localized("simulation__rate.hover");
+            ui.add(
+                DragValue::new(&mut self.rate)
+                    .range(MIN_RATE..=100.0)
+                    .speed(0.1)
+                    .suffix(" Hz"),
+            );
+            ui.end_row();
+            // Noise
+            ui.label(ui.localize("simulation__noise"))
+                .on_hover_localized("simulation__noise.hover");
+            ui.add(Slider::new(&mut self.noise, 0.0..=0.5));
+            ui.end_row();
+            // Drift
+            ui.label(ui.localize("simulation__drift"))
+                .on_hover_localized("simulation__drift.hover");
+            ui.add(Slider::new(&mut self.drift, -0.5..=0.5));
+            ui.end_row();
+            // Dropout
+            ui.label(ui.localize("simulation__dropout"))
+                .on_hover_localized("simulation__dropout.hover");
+            ui.add(Slider::new(&mut self.dropout, 0.0..=1.0));
+            ui.end_row();
+            // Step
+            ui.label(ui.localize("simulation__step"))
+                .on_hover_localized("simulation__step.hover");
+            ui.horizontal(|ui| {
+                ui.add(Slider::new(&mut self.step, -1.0..=1.0));
+                ui.add(
+                    DragValue::new(&mut self.step_period)
+                        .range(0..=86_400)
+                        .suffix(" s"),
+                );
+            });
+            ui.end_row();
+        });
+        ui.separator();
+        // Topics
+        for topic in TOPICS {
+            let mut checked = self.topics.contains(topic);
+            if ui.checkbox(&mut checked, topic).changed() {
+                if checked {
+                    self.topics.insert(topic.to_owned());
+                } else {
+                    self.topics.remove(topic);
+                }
+            }
+        }
+    }
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Simulator
+///
+/// Publishes synthetic device batches in the Arrow IPC format the viewer
+/// reads, through its own connection to the broker.
+#[derive(Debug, Default)]
+pub(crate) struct Simulator {
+    stop: Option<Arc<AtomicBool>>,
+    published: Arc<AtomicU64>,
+}
+
+impl Simulator {
+    pub(crate) fn is_running(&self) -> bool {
+        self.stop.is_some()
+    }
+
+    /// Number of published messages
+    pub(crate) fn published(&self) -> u64 {
+        self.published.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn start(&mut self, settings: &Settings, simulation: &Simulation) -> Result<()> {
+        self.stop();
+        let mut settings = settings.clone();
+        settings.client_id = format!("{}/simulator", settings.client_id());
+        let (client, mut connection) = Client::new(options(&settings)?, CAPACITY);
+        let stop = Arc::new(AtomicBool::new(false));
+        // The event loop has to be polled for the requests to be sent
+        let connection_stop = stop.clone();
+        spawn(move || {
+            for event in connection.iter() {
+                if connection_stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                if let Err(error) = event {
+                    error!(%error);
+                    sleep(Duration::from_secs(1));
+                }
+            }
+        });
+        let simulation = simulation.clone();
+        let publisher_stop = stop.clone();
+        let published = self.published.clone();
+        spawn(move || publisher(settings, simulation, client, publisher_stop, published));
+        self.stop = Some(stop);
+        Ok(())
+    }
+
+    pub(crate) fn stop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for Simulator {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[instrument(skip_all)]
+fn publisher(
+    settings: Settings,
+    simulation: Simulation,
+    client: Client,
+    stop: Arc<AtomicBool>,
+    published: Arc<AtomicU64>,
+) {
+    info!("Start simulation");
+    let period = Duration::from_secs_f64(1.0 / simulation.rate.max(MIN_RATE));
+    let start = Utc::now().timestamp_millis();
+    let mut random = Random::new(start as u64);
+    while !stop.load(Ordering::Relaxed) {
+        let timestamp = Utc::now().timestamp_millis();
+        for topic in &simulation.topics {
+            let Some(nominal) = nominal(topic) else {
+                continue;
+            };
+            let elapsed = (timestamp - start) as f64;
+            let mut identifiers = Vec::new();
+            let mut values = Vec::new();
+            for (index, &identifier) in simulation.identifiers.iter().enumerate() {
+                if random.next_f64() < simulation.dropout {
+                    continue;
+                }
+                // Each device has its own offset
+                let nominal = nominal * (1.0 + index as f64 * 0.05);
+                let mut value =
+                    nominal * (1.0 + simulation.drift * elapsed / MILLISECONDS_PER_HOUR);
+                if simulation.step_period > 0
+                    && (elapsed as u64 / (simulation.step_period * 1000)) % 2 == 1
+                {
+                    value += nominal * simulation.step;
+                }
+                value += nominal * simulation.noise * random.next_gaussian();
+                identifiers.push(identifier);
+                values.push(value);
+            }
+            if identifiers.is_empty() {
+                continue;
+            }
+            let result = batch(topic, timestamp, identifiers, values)
+                .and_then(|batch| encode(&batch))
+                .and_then(|payload| {
+                    Ok(client.publish(
+                        settings.topic(topic),
+                        settings.qos.into(),
+                        false,
+                        payload,
+                    )?)
+                });
+            match result {
+                Ok(()) => {
+                    published.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(error) => error!(%error),
+            }
+        }
+        sleep(period);
+    }
+    client.try_disconnect().ok();
+    info!("Stop simulation");
+}
+
+/// Nominal value of the device topic
+fn nominal(topic: &str) -> Option<f64> {
+    match topic {
+        TOPIC_ATUC => Some(1000.0),
+        TOPIC_DDOC_C1 | TOPIC_DDOC_C2 => Some(12.0),
+        TOPIC_DDOC_T1 | TOPIC_DDOC_T2 => Some(25.0),
+        TOPIC_DDOC_V1 => Some(8.0),
+        TOPIC_DDOC_V2 => Some(90.0),
+        TOPIC_DTEC => Some(25.0),
+        _ => None,
+    }
+}
+
+/// Device topic batch: identifier, timestamp and value columns
+fn batch(
+    topic: &str,
+    timestamp: i64,
+    identifiers: Vec<u64>,
+    values: Vec<f64>,
+) -> Result<RecordBatch> {
+    let name = name(topic).unwrap_or_default();
+    let timestamps = vec![timestamp; identifiers.len()];
+    let (data_type, values): (_, ArrayRef) = if topic == TOPIC_ATUC {
+        let values = values
+            .into_iter()
+            .map(|value| value.clamp(0.0, u16::MAX as _) as u16);
+        (
+            DataType::UInt16,
+            Arc::new(UInt16Array::from_iter_values(values)),
+        )
+    } else {
+        let values = values.into_iter().map(|value| value as f32);
+        (
+            DataType::Float32,
+            Arc::new(Float32Array::from_iter_values(values)),
+        )
+    };
+    let schema = Schema::new(vec![
+        Field::new(IDENTIFIER, DataType::UInt64, false),
+        Field::new(
+            TIMESTAMP,
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            false,
+        ),
+        Field::new(name, data_type, false),
+    ]);
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(identifiers)),
+            Arc::new(TimestampMillisecondArray::from(timestamps)),
+            values,
+        ],
+    )?)
+}
+
+/// Arrow IPC stream of the batch
+fn encode(batch: &RecordBatch) -> Result<Vec<u8>> {
+    let mut writer = StreamWriter::try_new(Vec::new(), &batch.schema())?;
+    writer.write(batch)?;
+    writer.finish()?;
+    Ok(writer.into_inner()?)
+}
+
+/// Xorshift pseudo random number generator, good enough for the noise
+#[derive(Clone, Copy, Debug)]
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        // The state must not be zero
+        Self(seed | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal, Box-Muller transform
+    fn next_gaussian(&mut self) -> f64 {
+        let u = 1.0 - self.next_f64();
+        let v = self.next_f64();
+        (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+    }
+}
@@ -0,0 +1,146 @@
+use super::Error;
+use chrono::{DateTime, Local, Utc};
+use egui::{Color32, Grid, RichText, Ui};
+use egui_l20n::UiExt as _;
+use egui_phosphor::regular::{ARROWS_CLOCKWISE, PLUG, PLUGS, PLUGS_CONNECTED};
+use std::{collections::BTreeMap, fmt::Display, time::Duration};
+
+const HMS: &str = "%H:%M:%S";
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Connection status
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Status {
+    pub(crate) state: State,
+    pub(crate) error: Option<String>,
+    pub(crate) last_message: Option<DateTime<Utc>>,
+    pub(crate) rejected: BTreeMap<String, Error>,
+}
+
+impl Status {
+    pub(crate) fn connected(&mut self) {
+        self.state = State::Connected;
+    }
+
+    pub(crate) fn reconnecting(&mut self, attempt: u32, error: impl Display) {
+        self.state = State::Reconnecting { attempt };
+        self.error = Some(error.to_string());
+    }
+
+    pub(crate) fn failed(&mut self, error: impl Display) {
+        self.state = State::Failed;
+        self.error = Some(error.to_string());
+    }
+
+    pub(crate) fn message(&mut self) {
+        self.last_message = Some(Utc::now());
+    }
+
+    /// Records the last rejected payload of the topic
+    pub(crate) fn reject(&mut self, topic: &str, error: Error) {
+        self.rejected.insert(topic.to_owned(), error);
+    }
+
+    /// Forgets the rejection once the topic validates again
+    pub(crate) fn accept(&mut self, topic: &str) {
+        self.rejected.remove(topic);
+    }
+}
+
+impl Status {
+    pub(crate) fn show(&self, ui: &mut Ui) {
+        let text = format!("{} {}", self.state.icon(), ui.localize(self.state.text()));
+        ui.label(RichText::new(text).color(self.state.color()))
+            .on_hover_ui(|ui| {
+                Grid::new(ui.next_auto_id()).show(ui, |ui| {
+                    if let State::Reconnecting { attempt } = self.state {
+                        ui.label(ui.localize("mqtt__attempt"));
+                        ui.label(attempt.to_string());
+                        ui.end_row();
+                    }
+                    ui.label(ui.localize("mqtt__last_message"));
+                    if let Some(last_message) = self.last_message {
+                        let seconds = (Utc::now() - last_message).num_seconds();
+                        ui.label(format!(
+                            "{} ({})",
+                            last_message.with_timezone(&Local).format(HMS),
+                            ui.localize(&format!("mqtt__seconds_ago?seconds={seconds}")),
+                        ));
+                    } else {
+                        ui.label("-");
+                    }
+                    ui.end_row();
+                    ui.label(ui.localize("mqtt__last_error"));
+                    ui.label(self.error.as_deref().unwrap_or("-"));
+                    ui.end_row();
+                    for (topic, error) in &self.rejected {
+                        ui.label(ui.localize(&format!("mqtt__rejected?topic={topic}")));
+                        ui.label(error.to_string());
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+}
+
+/// Connection state
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum State {
+    #[default]
+    Connecting,
+    Connected,
+    Reconnecting {
+        attempt: u32,
+    },
+    Failed,
+}
+
+impl State {
+    pub(crate) fn text(&self) -> &'static str {
+        match self {
+            Self::Connecting => "mqtt__connecting",
+            Self::Connected => "mqtt__connected",
+            Self::Reconnecting { .. } => "mqtt__reconnecting",
+            Self::Failed => "mqtt__failed",
+        }
+    }
+
+    pub(crate) const fn icon(&self) -> &'static str {
+        match self {
+            Self::Connecting => PLUG,
+            Self::Connected => PLUGS_CONNECTED,
+            Self::Reconnecting { .. } => ARROWS_CLOCKWISE,
+            Self::Failed => PLUGS,
+        }
+    }
+
+    pub(crate) const fn color(&self) -> Color32 {
+        match self {
+            Self::Connecting | Self::Reconnecting { .. } => Color32::YELLOW,
+            Self::Connected => Color32::GREEN,
+            Self::Failed => Color32::RED,
+        }
+    }
+}
+
+/// Exponential backoff
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Backoff {
+    pub(crate) attempt: u32,
+}
+
+impl Backoff {
+    /// Delay before the next attempt
+    pub(crate) fn next(&mut self) -> Duration {
+        let delay = MIN_BACKOFF
+            .saturating_mul(2u32.saturating_pow(self.attempt))
+            .min(MAX_BACKOFF);
+        self.attempt += 1;
+        delay
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
@@ -0,0 +1,106 @@
+use super::Error;
+use arrow::{array::RecordBatch, datatypes::SchemaRef};
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use egui::{Grid, Ui};
+use egui_l20n::UiExt as _;
+use std::collections::{BTreeMap, VecDeque};
+
+const HMS: &str = "%H:%M:%S";
+const RATE_WINDOW: TimeDelta = TimeDelta::seconds(10);
+
+/// Topics seen under the subscription, relative to the prefix
+#[derive(Debug, Default)]
+pub(crate) struct Topics(BTreeMap<String, Topic>);
+
+impl Topics {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &Topic)> {
+        self.0.iter()
+    }
+
+    /// Records the message and its decoded schema or error
+    pub(crate) fn record(&mut self, topic: &str, result: Result<&RecordBatch, &Error>) {
+        let now = Utc::now();
+        let seen = self.0.entry(topic.to_owned()).or_default();
+        seen.messages += 1;
+        seen.times.push_back(now);
+        while seen
+            .times
+            .front()
+            .is_some_and(|&time| now - time > RATE_WINDOW)
+        {
+            seen.times.pop_front();
+        }
+        match result {
+            Ok(batch) => {
+                seen.schema = Some(batch.schema());
+                seen.error = None;
+            }
+            Err(error) => seen.error = Some(error.clone()),
+        }
+    }
+}
+
+/// Seen topic
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Topic {
+    pub(crate) messages: u64,
+    pub(crate) schema: Option<SchemaRef>,
+    pub(crate) error: Option<Error>,
+    times: VecDeque<DateTime<Utc>>,
+}
+
+impl Topic {
+    /// Messages per second over the rate window
+    pub(crate) fn rate(&self) -> f64 {
+        let seconds = RATE_WINDOW.num_seconds() as f64;
+        let now = Utc::now();
+        let count = self
+            .times
+            .iter()
+            .filter(|&&time| now - time <= RATE_WINDOW)
+            .count();
+        count as f64 / seconds
+    }
+
+    pub(crate) fn last_message(&self) -> Option<DateTime<Utc>> {
+        self.times.back().copied()
+    }
+}
+
+impl Topic {
+    pub(crate) fn show(&self, ui: &mut Ui) {
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            ui.label(ui.localize("topic__messages"));
+            ui.label(self.messages.to_string());
+            ui.end_row();
+            ui.label(ui.localize("topic__rate"));
+            ui.label(ui.localize(&format!("topic__per_second?rate={:.1}", self.rate())));
+            ui.end_row();
+            ui.label(ui.localize("mqtt__last_message"));
+            if let Some(last_message) = self.last_message() {
+                ui.label(last_message.with_timezone(&Local).format(HMS).to_string());
+            } else {
+                ui.label("-");
+            }
+            ui.end_row();
+            if let Some(schema) = &self.schema {
+                ui.label(ui.localize("topic__schema"));
+                ui.vertical(|ui| {
+                    for field in schema.fields() {
+                        ui.label(format!("{}: {}", field.name(), field.data_type()));
+                    }
+                });
+                ui.end_row();
+            }
+            if let Some(error) = &self.error {
+                ui.label(ui.localize("mqtt__last_error"));
+                ui.label(error.to_string());
+                ui.end_row();
+            }
+        });
+    }
+}
@@ -0,0 +1,375 @@
+use super::IDENTIFIER;
+use arrow::{
+    array::{AsArray as _, RecordBatch},
+    datatypes::UInt64Type,
+};
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use egui::{Checkbox, DragValue, Grid, RichText, Ui};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::WARNING;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const HMS: &str = "%H:%M:%S";
+const FACTOR: f64 = 3.0;
+const MINIMUM: u64 = 5;
+/// Weight of the last interval in the learned cadence
+const SMOOTHING: f64 = 0.2;
+/// Audible cue
+const FREQUENCY: f32 = 880.0;
+const DURATION: f64 = 0.3;
+
+/// Staleness settings
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Staleness {
+    /// A stream is stale after this many expected intervals without data
+    pub(crate) factor: f64,
+    /// Minimum silence before a stream is stale, seconds
+    pub(crate) minimum: u64,
+    /// Configured cadence by topic relative to the prefix, seconds, the
+    /// learned one is used if missing
+    pub(crate) cadences: BTreeMap<String, u64>,
+    /// Beep when a stream becomes stale
+    pub(crate) sound: bool,
+}
+
+impl Staleness {
+    pub(crate) fn new() -> Self {
+        Self {
+            factor: FACTOR,
+            minimum: MINIMUM,
+            cadences: BTreeMap::new(),
+            sound: false,
+        }
+    }
+}
+
+impl Staleness {
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        let watchdog = super::watchdog(ui.ctx());
+        let watchdog = watchdog.lock();
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            ui.label(ui.localize("staleness__factor"))
+                .on_hover_localized("staleness__factor.hover");
+            ui.add(
+                DragValue::new(&mut self.factor)
+                    .range(1.0..=100.0)
+                    .speed(0.1),
+            )
+            .on_hover_localized("staleness__factor.hover");
+            ui.end_row();
+            ui.label(ui.localize("staleness__minimum"))
+                .on_hover_localized("staleness__minimum.hover");
+            ui.add(
+                DragValue::new(&mut self.minimum)
+                    .range(1..=u64::MAX)
+                    .suffix(" s"),
+            )
+            .on_hover_localized("staleness__minimum.hover");
+            ui.end_row();
+            ui.label(ui.localize("staleness__sound"))
+                .on_hover_localized("staleness__sound.hover");
+            ui.add(Checkbox::without_text(&mut self.sound))
+                .on_hover_localized("staleness__sound.hover");
+            ui.end_row();
+        });
+        if watchdog.streams.is_empty() {
+            return;
+        }
+        ui.separator();
+        let now = Utc::now();
+        Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+            ui.label(ui.localize("staleness__topic"));
+            ui.label(ui.localize("staleness__identifier"));
+            ui.label(ui.localize("mqtt__last_message"));
+            ui.label(ui.localize("staleness__cadence"))
+                .on_hover_localized("staleness__cadence.hover");
+            ui.label(ui.localize("staleness__configured"))
+                .on_hover_localized("staleness__configured.hover");
+            ui.end_row();
+            let mut previous = None;
+            for ((topic, identifier), stream) in &watchdog.streams {
+                let text = RichText::new(topic);
+                if stream.stale {
+                    ui.label(text.color(ui.visuals().error_fg_color));
+                } else {
+                    ui.label(text);
+                }
+                ui.label(format!("{identifier:x}"));
+                ui.label(format!(
+                    "{} ({:.0} s)",
+                    stream.last_seen.with_timezone(&Local).format(HMS),
+                    (now - stream.last_seen).as_seconds_f64(),
+                ));
+                match stream.cadence {
+                    Some(cadence) => ui.label(format!("{:.1} s", cadence / 1000.0)),
+                    None => ui.label("-"),
+                };
+                // Once per topic
+                if previous != Some(topic) {
+                    ui.horizontal(|ui| {
+                        let mut configured = self.cadences.contains_key(topic);
+                        if ui
+                            .add(Checkbox::without_text(&mut configured))
+                            .on_hover_localized("staleness__configured.hover")
+                            .changed()
+                        {
+                            if configured {
+                                let learned = stream.cadence.map_or(MINIMUM, |cadence| {
+                                    (cadence / 1000.0).ceil().max(1.0) as _
+                                });
+                                self.cadences.insert(topic.clone(), learned);
+                            } else {
+                                self.cadences.remove(topic);
+                            }
+                        }
+                        if let Some(seconds) = self.cadences.get_mut(topic) {
+                            ui.add(DragValue::new(seconds).range(1..=u64::MAX).suffix(" s"));
+                        }
+                    });
+                }
+                previous = Some(topic);
+                ui.end_row();
+            }
+        });
+    }
+}
+
+impl Default for Staleness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Staleness watchdog
+///
+/// Tracks the last seen time and the cadence of each topic and identifier
+/// stream.
+#[derive(Debug, Default)]
+pub(crate) struct Watchdog {
+    staleness: Staleness,
+    streams: BTreeMap<(String, u64), Stream>,
+}
+
+impl Watchdog {
+    pub(crate) fn configure(&mut self, staleness: Staleness) {
+        self.staleness = staleness;
+    }
+
+    /// Records the arrival of the batch identifiers
+    pub(crate) fn feed(&mut self, topic: &str, batch: &RecordBatch) {
+        let Some(identifiers) = batch
+            .column_by_name(IDENTIFIER)
+            .and_then(|column| column.as_primitive_opt::<UInt64Type>())
+        else {
+            return;
+        };
+        let now = Utc::now();
+        let identifiers: BTreeSet<_> = identifiers.iter().flatten().collect();
+        for identifier in identifiers {
+            self.streams
+                .entry((topic.to_owned(), identifier))
+                .and_modify(|stream| stream.seen(now))
+                .or_insert_with(|| Stream::new(now));
+        }
+    }
+
+    /// Updates the stale flags, returns the number of streams which became
+    /// stale
+    pub(crate) fn update(&mut self) -> usize {
+        let now = Utc::now();
+        let mut count = 0;
+        for ((topic, _), stream) in &mut self.streams {
+            let limit = self
+                .staleness
+                .cadences
+                .get(topic)
+                .map(|&seconds| seconds as f64 * 1000.0)
+                .or(stream.cadence)
+                .map_or(f64::INFINITY, |cadence| {
+                    (cadence * self.staleness.factor).max(self.staleness.minimum as f64 * 1000.0)
+                });
+            let stale = (now - stream.last_seen).num_milliseconds() as f64 > limit;
+            if stale && !stream.stale {
+                count += 1;
+            }
+            stream.stale = stale;
+        }
+        count
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    pub(crate) fn sound(&self) -> bool {
+        self.staleness.sound
+    }
+
+    /// Stale streams of the topic
+    pub(crate) fn stale(&self, topic: &str) -> impl Iterator<Item = (u64, &Stream)> {
+        self.streams
+            .iter()
+            .filter(move |((candidate, _), stream)| candidate == topic && stream.stale)
+            .map(|(&(_, identifier), stream)| (identifier, stream))
+    }
+
+    /// Topics with at least one stale stream
+    pub(crate) fn stale_topics(&self) -> BTreeSet<String> {
+        self.streams
+            .iter()
+            .filter(|(_, stream)| stream.stale)
+            .map(|((topic, _), _)| topic.clone())
+            .collect()
+    }
+
+    /// Forgets the streams, e.g. when the connection settings change
+    pub(crate) fn clear(&mut self) {
+        self.streams.clear();
+    }
+}
+
+/// Topic and identifier stream
+#[derive(Clone, Debug)]
+pub(crate) struct Stream {
+    pub(crate) last_seen: DateTime<Utc>,
+    /// Learned interval between arrivals, milliseconds
+    pub(crate) cadence: Option<f64>,
+    pub(crate) stale: bool,
+}
+
+impl Stream {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            last_seen: now,
+            cadence: None,
+            stale: false,
+        }
+    }
+
+    fn seen(&mut self, now: DateTime<Utc>) {
+        let interval = (now - self.last_seen).num_milliseconds() as f64;
+        self.cadence = Some(match self.cadence {
+            Some(cadence) => cadence + SMOOTHING * (interval - cadence),
+            None => interval,
+        });
+        self.last_seen = now;
+        self.stale = false;
+    }
+
+    /// Silence since the last arrival
+    pub(crate) fn silence(&self) -> TimeDelta {
+        Utc::now() - self.last_seen
+    }
+}
+
+impl Stream {
+    pub(crate) fn show(&self, ui: &mut Ui, identifier: u64) {
+        ui.label(
+            RichText::new(format!(
+                "{WARNING} {identifier:x}: {}",
+                ui.localize(&format!(
+                    "staleness__silent?seconds={}",
+                    self.silence().num_seconds(),
+                )),
+            ))
+            .color(ui.visuals().error_fg_color),
+        );
+    }
+}
+
+/// Plays a short audible cue through the audio player of the system
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn beep() {
+    use anyhow::{Result, bail};
+    use std::{env::temp_dir, fs, process::Command};
+
+    const SAMPLE_RATE: u32 = 8000;
+
+    // 16 bit mono PCM
+    fn wave() -> Vec<u8> {
+        let samples = (SAMPLE_RATE as f64 * DURATION) as u32;
+        let size = samples * 2;
+        let mut bytes = Vec::with_capacity(44 + size as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + size).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+        bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&size.to_le_bytes());
+        for index in 0..samples {
+            let time = index as f32 / SAMPLE_RATE as f32;
+            let sample = (time * FREQUENCY * std::f32::consts::TAU).sin() * i16::MAX as f32 / 2.0;
+            bytes.extend_from_slice(&(sample as i16).to_le_bytes());
+        }
+        bytes
+    }
+
+    fn play() -> Result<()> {
+        let path = temp_dir().join("viewer_beep.wav");
+        if !path.exists() {
+            fs::write(&path, wave())?;
+        }
+        let path = path.display().to_string();
+        let players = if cfg!(target_os = "windows") {
+            vec![(
+                "powershell",
+                vec![
+                    "-NoProfile".to_owned(),
+                    "-Command".to_owned(),
+                    format!("(New-Object Media.SoundPlayer '{path}').PlaySync()"),
+                ],
+            )]
+        } else if cfg!(target_os = "macos") {
+            vec![("afplay", vec![path])]
+        } else {
+            vec![
+                ("paplay", vec![path.clone()]),
+                ("aplay", vec!["-q".to_owned(), path]),
+            ]
+        };
+        for (program, arguments) in players {
+            if Command::new(program)
+                .args(arguments)
+                .status()
+                .is_ok_and(|status| status.success())
+            {
+                return Ok(());
+            }
+        }
+        bail!("No audio player")
+    }
+
+    // The player blocks until the end of the sound
+    std::thread::spawn(|| {
+        if let Err(error) = play() {
+            tracing::error!(%error);
+        }
+    });
+}
+
+/// Plays a short audible cue
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn beep() {
+    use web_sys::AudioContext;
+
+    let beep = || -> Result<(), wasm_bindgen::JsValue> {
+        let context = AudioContext::new()?;
+        let oscillator = context.create_oscillator()?;
+        oscillator.frequency().set_value(FREQUENCY);
+        oscillator.connect_with_audio_node(&context.destination())?;
+        oscillator.start()?;
+        oscillator.stop_with_when(context.current_time() + DURATION)?;
+        Ok(())
+    };
+    if let Err(error) = beep() {
+        tracing::error!(?error);
+    }
+}
@@ -0,0 +1,178 @@
+use super::{Settings, Status, TOPIC, packet, packet::Packet, receive};
+use anyhow::{Result, bail, ensure};
+use bytes::BytesMut;
+use egui::{Context, mutex::Mutex};
+use ewebsock::{Options, WsEvent, WsMessage, WsReceiver, WsSender};
+use std::time::Duration;
+use tracing::trace;
+
+const SUBPROTOCOL: &str = "mqtt";
+const SUBSCRIBE_PACKET_ID: u16 = 1;
+/// Silence after which the connection is considered dead, keep alives
+const TIMEOUT: f64 = 1.5;
+
+/// MQTT over WebSocket client
+pub(super) struct Client {
+    sender: WsSender,
+    receiver: WsReceiver,
+    buffer: BytesMut,
+    /// Time of the last sent packet, seconds
+    last: f64,
+    /// Time of the last received packet, seconds
+    received: f64,
+    opened: bool,
+    /// Identifier of the last published packet
+    packet_id: u16,
+}
+
+impl Client {
+    pub(super) fn connect(context: &Context, settings: &Settings) -> Result<Self> {
+        let options = Options {
+            subprotocols: vec![SUBPROTOCOL.to_owned()],
+            ..Default::default()
+        };
+        let wake_up = {
+            let context = context.clone();
+            move || context.request_repaint()
+        };
+        let (sender, receiver) =
+            match ewebsock::connect_with_wakeup(settings.url(), options, wake_up) {
+                Ok(connection) => connection,
+                Err(error) => bail!(error),
+            };
+        Ok(Self {
+            sender,
+            receiver,
+            buffer: BytesMut::new(),
+            last: 0.0,
+            received: 0.0,
+            opened: false,
+            packet_id: SUBSCRIBE_PACKET_ID,
+        })
+    }
+
+    pub(super) fn disconnect(&mut self) {
+        if self.opened {
+            self.sender.send(WsMessage::Binary(packet::disconnect()));
+        }
+    }
+
+    pub(super) fn publish(
+        &mut self,
+        context: &Context,
+        topic: &str,
+        qos: u8,
+        retain: bool,
+        payload: &[u8],
+    ) -> Result<()> {
+        ensure!(self.opened, "Not connected");
+        let packet_id = (qos > 0).then(|| {
+            // Zero is not a valid packet identifier, the subscription one is reserved
+            self.packet_id = self
+                .packet_id
+                .checked_add(1)
+                .unwrap_or(SUBSCRIBE_PACKET_ID + 1);
+            self.packet_id
+        });
+        self.send(
+            context,
+            packet::publish(topic, qos, retain, packet_id, payload),
+        );
+        Ok(())
+    }
+
+    /// Processes the received WebSocket events and keeps the connection alive
+    pub(super) fn poll(
+        &mut self,
+        context: &Context,
+        settings: &Settings,
+        status: &Mutex<Status>,
+    ) -> Result<()> {
+        while let Some(event) = self.receiver.try_recv() {
+            match event {
+                WsEvent::Opened => {
+                    self.opened = true;
+                    self.received = context.input(|input| input.time);
+                    self.send(
+                        context,
+                        packet::connect(
+                            &settings.client_id(),
+                            settings.keep_alive as _,
+                            &settings.username,
+                            &settings.password,
+                        ),
+                    );
+                }
+                WsEvent::Message(WsMessage::Binary(bytes)) => {
+                    self.buffer.extend_from_slice(&bytes);
+                    while let Some(packet) = packet::decode(&mut self.buffer)? {
+                        self.received = context.input(|input| input.time);
+                        self.handle(context, settings, status, packet)?;
+                    }
+                }
+                WsEvent::Message(message) => trace!(?message),
+                WsEvent::Error(error) => bail!(error),
+                WsEvent::Closed => bail!("WebSocket closed"),
+            }
+        }
+        // Keep alive
+        let now = context.input(|input| input.time);
+        let keep_alive = settings.keep_alive as f64;
+        // A half-open connection gets no PINGRESP
+        if self.opened && keep_alive > 0.0 && now - self.received >= TIMEOUT * keep_alive {
+            bail!("No response for {:.0} s", now - self.received);
+        }
+        let interval = keep_alive / 2.0;
+        if self.opened && now - self.last >= interval {
+            self.send(context, packet::pingreq());
+        }
+        context.request_repaint_after(Duration::from_secs_f64(interval));
+        Ok(())
+    }
+
+    fn handle(
+        &mut self,
+        context: &Context,
+        settings: &Settings,
+        status: &Mutex<Status>,
+        packet: Packet,
+    ) -> Result<()> {
+        match packet {
+            Packet::ConnAck { code: 0 } => {
+                status.lock().connected();
+                let topic = settings.topic(TOPIC);
+                self.send(
+                    context,
+                    packet::subscribe(SUBSCRIBE_PACKET_ID, &topic, settings.qos.into()),
+                );
+            }
+            Packet::ConnAck { code } => bail!("Connection refused, return code {code}"),
+            Packet::Publish {
+                qos,
+                packet_id,
+                topic,
+                payload,
+            } => {
+                match (qos, packet_id) {
+                    (1, Some(packet_id)) => self.send(context, packet::puback(packet_id)),
+                    (2, Some(packet_id)) => self.send(context, packet::pubrec(packet_id)),
+                    _ => {}
+                }
+                status.lock().message();
+                match receive(context, settings, &topic, payload) {
+                    Ok(()) => status.lock().accept(&topic),
+                    Err(error) => status.lock().reject(&topic, error),
+                }
+            }
+            Packet::PubRec { packet_id } => self.send(context, packet::pubrel(packet_id)),
+            Packet::PubRel { packet_id } => self.send(context, packet::pubcomp(packet_id)),
+            packet => trace!(?packet),
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, context: &Context, bytes: Vec<u8>) {
+        self.sender.send(WsMessage::Binary(bytes));
+        self.last = context.input(|input| input.time);
+    }
+}
@@ -1,24 +1,41 @@
 use super::Pane;
 use egui::{
-    CentralPanel, RichText, ScrollArea, Sides, TextStyle, TopBottomPanel, Ui, Vec2, WidgetText,
-    menu::bar, vec2,
+    CentralPanel, Color32, RichText, ScrollArea, Sides, Stroke, StrokeKind, TextStyle,
+    TopBottomPanel, Ui, Vec2, WidgetText, menu::bar, vec2,
 };
-use egui_phosphor::regular::X;
+use egui_phosphor::regular::{WARNING, X};
 use egui_tiles::{Tile, TileId, Tiles, UiResponse};
 use egui_tiles_ext::ContainerExt as _;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 const MARGIN: Vec2 = vec2(4.0, 2.0);
+const STALE_STROKE_WIDTH: f32 = 4.0;
 
 /// Behavior
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub(crate) struct Behavior {
     pub(crate) close: Option<TileId>,
+    /// Topics with stale streams
+    #[serde(skip)]
+    pub(crate) stale: BTreeSet<String>,
+    /// Title color of the stale panes, the tab titles have no access to the
+    /// visuals
+    #[serde(skip)]
+    pub(crate) stale_color: Color32,
 }
 
 impl Behavior {
-    pub(crate) fn new() -> Self {
-        Self { close: None }
+    pub(crate) fn new(stale: BTreeSet<String>, stale_color: Color32) -> Self {
+        Self {
+            close: None,
+            stale,
+            stale_color,
+        }
+    }
+
+    fn is_stale(&self, pane: &Pane) -> bool {
+        pane.topic().is_some_and(|topic| self.stale.contains(topic))
     }
 }
 
@@ -56,7 +73,13 @@ impl Behavior {
 
 impl egui_tiles::Behavior<Pane> for Behavior {
     fn tab_title_for_pane(&mut self, pane: &Pane) -> WidgetText {
-        format!("{} {}", pane.icon(), pane.title()).into()
+        if self.is_stale(pane) {
+            RichText::new(format!("{WARNING} {} {}", pane.icon(), pane.title()))
+                .color(self.stale_color)
+                .into()
+        } else {
+            format!("{} {}", pane.icon(), pane.title()).into()
+        }
     }
 
     fn tab_title_for_tile(&mut self, tiles: &Tiles<Pane>, tile_id: TileId) -> WidgetText {
@@ -105,6 +128,15 @@ impl egui_tiles::Behavior<Pane> for Behavior {
         CentralPanel::default().show_inside(ui, |ui| {
             pane.body(ui);
         });
+        // A dead sensor has to be visible from across the room
+        if self.is_stale(pane) {
+            ui.painter().rect_stroke(
+                ui.max_rect(),
+                0.0,
+                Stroke::new(STALE_STROKE_WIDTH, ui.visuals().error_fg_color),
+                StrokeKind::Inside,
+            );
+        }
         if response.dragged() {
             UiResponse::DragStarted
         } else {
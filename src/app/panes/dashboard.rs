@@ -0,0 +1,165 @@
+use super::{ID_SOURCE, Kind, settings::Settings};
+use crate::{
+    app::{
+        YMDHMS,
+        alarms::{self, Level},
+        computers::{DashboardComputed, DashboardKey},
+        metadata::MetaDataFrame,
+        mqtt,
+    },
+    utils::hashed::Hashed,
+};
+use chrono::Utc;
+use egui::{Frame, Grid, RichText, ScrollArea, Ui};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::WARNING;
+use egui_plot::{Line, Plot, PlotPoints};
+use std::collections::BTreeSet;
+
+const VALUE_SIZE: f32 = 64.0;
+const UNIT_SIZE: f32 = 32.0;
+const CARD_WIDTH: f32 = 280.0;
+const SPARKLINE_HEIGHT: f32 = 48.0;
+
+/// Dashboard view
+#[derive(Debug)]
+pub(crate) struct View<'a> {
+    /// Topic relative to the prefix
+    topic: &'a str,
+    real_time: bool,
+    /// Unit of the single value column
+    unit: &'a str,
+    frame: &'a Hashed<MetaDataFrame>,
+    settings: &'a Settings,
+}
+
+impl<'a> View<'a> {
+    pub(crate) const fn new(
+        topic: &'a str,
+        real_time: bool,
+        unit: &'a str,
+        frame: &'a Hashed<MetaDataFrame>,
+        settings: &'a Settings,
+    ) -> Self {
+        Self {
+            topic,
+            real_time,
+            unit,
+            frame,
+            settings,
+        }
+    }
+}
+
+impl View<'_> {
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        let now = self
+            .real_time
+            .then(|| Utc::now().timestamp_millis() / 1000 * 1000);
+        let value = ui.memory_mut(|memory| {
+            memory
+                .caches
+                .cache::<DashboardComputed>()
+                .get(DashboardKey {
+                    frame: self.frame,
+                    settings: self.settings,
+                    now,
+                })
+        });
+        // Devices the watchdog considers silent
+        let stale = if self.real_time {
+            mqtt::watchdog(ui.ctx())
+                .lock()
+                .stale(self.topic)
+                .map(|(identifier, _)| identifier)
+                .collect()
+        } else {
+            BTreeSet::new()
+        };
+        let precision = self.settings.dashboard.precision;
+        // The value column is named only in a wide frame
+        let wide = self.settings.values.selected(&self.frame.data).len() > 1;
+        ScrollArea::vertical().show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for ((identifier, column), card) in value.cards {
+                    let (name, unit) = if wide {
+                        let unit = Kind::from_name(&column).map_or("", |kind| kind.unit());
+                        (format!("{identifier:x} {column}"), unit)
+                    } else {
+                        (format!("{identifier:x}"), self.unit)
+                    };
+                    let (color, line_color) = match alarms::level(
+                        ui.ctx(),
+                        self.topic,
+                        identifier,
+                        &column,
+                        card.value,
+                    ) {
+                        Level::Normal => {
+                            (ui.visuals().strong_text_color(), ui.visuals().text_color())
+                        }
+                        Level::Warning => (ui.visuals().warn_fg_color, ui.visuals().warn_fg_color),
+                        Level::Alarm => (ui.visuals().error_fg_color, ui.visuals().error_fg_color),
+                    };
+                    Frame::group(ui.style()).show(ui, |ui| {
+                        ui.set_width(CARD_WIDTH);
+                        ui.vertical(|ui| {
+                            ui.heading(&name);
+                            // Current value
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(format!("{:.precision$}", card.value))
+                                        .size(VALUE_SIZE)
+                                        .color(color),
+                                );
+                                ui.label(RichText::new(unit).size(UNIT_SIZE).color(color));
+                            });
+                            let time = self.settings.time_zone.format_time(card.timestamp, YMDHMS);
+                            if stale.contains(&identifier) {
+                                ui.label(
+                                    RichText::new(format!("{WARNING} {time}"))
+                                        .color(ui.visuals().error_fg_color),
+                                )
+                                .on_hover_localized("staleness__stale");
+                            } else {
+                                ui.label(time);
+                            }
+                            // Statistics over the window
+                            if let Some(statistics) = card.statistics {
+                                Grid::new(ui.next_auto_id()).show(ui, |ui| {
+                                    ui.label(ui.localize("dashboard__min"));
+                                    ui.label(ui.localize("dashboard__max"));
+                                    ui.label(ui.localize("dashboard__mean"));
+                                    ui.end_row();
+                                    ui.label(format!("{:.precision$}", statistics.min));
+                                    ui.label(format!("{:.precision$}", statistics.max));
+                                    ui.label(format!("{:.precision$}", statistics.mean));
+                                    ui.end_row();
+                                });
+                            }
+                            // Last hour
+                            Plot::new(ui.id().with((ID_SOURCE, "Sparkline", &name)))
+                                .height(SPARKLINE_HEIGHT)
+                                .show_axes(false)
+                                .show_grid(false)
+                                .show_x(false)
+                                .show_y(false)
+                                .allow_boxed_zoom(false)
+                                .allow_double_click_reset(false)
+                                .allow_drag(false)
+                                .allow_scroll(false)
+                                .allow_zoom(false)
+                                .show_background(false)
+                                .show(ui, |ui| {
+                                    ui.line(
+                                        Line::new(name, PlotPoints::new(card.sparkline))
+                                            .color(line_color),
+                                    );
+                                });
+                        });
+                    });
+                }
+            });
+        });
+    }
+}
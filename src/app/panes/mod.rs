@@ -1,4 +1,5 @@
 use self::{
+    dashboard::View as DashboardView,
     plot::View as PlotView,
     settings::Settings,
     state::State,
@@ -8,27 +9,35 @@ use self::{
 use super::metadata::{ICON, MAX_TIMESTAMP, MIN_TIMESTAMP, NAME};
 use crate::{
     app::{
-        YMDHMS,
+        NAME_DDOC_C1, NAME_DDOC_C2, NAME_DDOC_T1, NAME_DDOC_T2, NAME_DDOC_V1, NAME_DDOC_V2,
+        NAME_TEMPERATURE, NAME_TURBIDITY, YMDHMS,
         computers::{TableComputed, TableKey},
-        metadata::{MetaDataFrame, Metadata},
+        meta,
+        metadata::{MetaDataFrame, Metadata, MetadataWidget},
         mqtt::{
-            TOPIC_ATUC, TOPIC_DDOC_C1, TOPIC_DDOC_C2, TOPIC_DDOC_T1, TOPIC_DDOC_T2, TOPIC_DDOC_V1,
-            TOPIC_DDOC_V2, TOPIC_DTEC,
+            self, TOPIC_ATUC, TOPIC_DDOC_C1, TOPIC_DDOC_C2, TOPIC_DDOC_T1, TOPIC_DDOC_T2,
+            TOPIC_DDOC_V1, TOPIC_DDOC_V2, TOPIC_DTEC,
         },
+        values,
     },
-    export::xlsx,
+    export::{ipc, xlsx},
     utils::hashed::Hashed,
 };
-use anyhow::Result;
+use anyhow::{Result, bail};
 use chrono::NaiveDateTime;
-use egui::{CursorIcon, Response, RichText, Ui, Window, util::hash};
+use egui::{Context, CursorIcon, Grid, Response, RichText, ScrollArea, Ui, Window, util::hash};
 use egui_l20n::{ResponseExt, UiExt as _};
-use egui_phosphor::regular::{ARROWS_CLOCKWISE, ARROWS_HORIZONTAL, FLOPPY_DISK, GEAR, MINUS};
+use egui_phosphor::regular::{
+    ARROWS_CLOCKWISE, ARROWS_HORIZONTAL, BROADCAST, CLOCK, DROP, DROP_HALF, FLOPPY_DISK, GEAR,
+    INFO, LIGHTNING, MINUS, THERMOMETER, WARNING,
+};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 const ID_SOURCE: &str = "Pane";
+const IDENTIFIER: &str = "Identifier";
+const TIMESTAMP: &str = "Timestamp";
 
 /// Pane
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -38,6 +47,13 @@ pub(crate) struct Pane {
     pub(crate) settings: Settings,
     pub(crate) state: State,
     pub(crate) view: View,
+    #[serde(default)]
+    pub(crate) real_time: bool,
+    /// Topic relative to the prefix, set for generic panes
+    #[serde(default)]
+    pub(crate) topic: String,
+    #[serde(skip)]
+    pub(crate) version: Option<u64>,
 }
 
 impl Pane {
@@ -62,11 +78,25 @@ impl Pane {
             settings: Settings::new(),
             state: State::new(),
             view: View::Table,
+            real_time: true,
+            topic: String::new(),
+            version: None,
+        }
+    }
+
+    /// Generic live pane of a discovered topic
+    pub(crate) fn generic(topic: String) -> Self {
+        Self {
+            topic,
+            ..Self::new(Kind::Generic)
         }
     }
 
     pub(crate) fn icon(&self) -> &str {
-        &self.frame.meta[ICON]
+        self.frame
+            .meta
+            .get(ICON)
+            .map_or(self.kind.icon(), String::as_str)
     }
 
     pub(crate) fn title(&self) -> String {
@@ -84,26 +114,41 @@ impl Pane {
         //         .unwrap()
         //         .format(DATE_TIME_FORMAT)
         //         .to_string();
-        let min_timestamp = &self.frame.meta[MIN_TIMESTAMP];
-        let max_timestamp = &self.frame.meta[MAX_TIMESTAMP];
-        format!("{min_timestamp} {MINUS} {max_timestamp}")
+        let (Some(min_timestamp), Some(max_timestamp)) = (
+            self.frame.meta.get(MIN_TIMESTAMP),
+            self.frame.meta.get(MAX_TIMESTAMP),
+        ) else {
+            return format!("{CLOCK} {}", self.source());
+        };
+        if self.is_real_time() {
+            format!("{CLOCK} {min_timestamp} {MINUS} {max_timestamp}")
+        } else {
+            format!("{min_timestamp} {MINUS} {max_timestamp}")
+        }
     }
 
     pub(crate) fn name(&self) -> &str {
-        &self.frame.meta[NAME]
+        self.frame
+            .meta
+            .get(NAME)
+            .map_or(self.source(), String::as_str)
     }
 
-    pub(crate) const fn topic(&self) -> Option<&str> {
-        if self.is_real_time() {
-            Some(self.kind.topic())
-        } else {
-            None
+    /// Subscribed topic of the real time pane
+    pub(crate) fn topic(&self) -> Option<&str> {
+        self.is_real_time().then(|| self.source())
+    }
+
+    /// Topic relative to the prefix
+    fn source(&self) -> &str {
+        match self.kind {
+            Kind::Generic => &self.topic,
+            kind => kind.topic(),
         }
     }
 
     pub(crate) const fn is_real_time(&self) -> bool {
-        // self.source.is_none()
-        false
+        self.real_time
     }
 
     pub(crate) fn text(&self) -> &'static str {
@@ -124,6 +169,7 @@ impl Pane {
             Kind::Ddoc(Ddoc::V1) => "digital_disolved_oxygen_controller_voltage_channel?index=1",
             Kind::Ddoc(Ddoc::V2) => "digital_disolved_oxygen_controller_voltage_channel?index=2",
             Kind::Dtec => "digital_temperature_controller.abbreviation",
+            Kind::Generic => "generic_topic",
         }
     }
 
@@ -145,6 +191,7 @@ impl Pane {
             Kind::Ddoc(Ddoc::V1) => "digital_disolved_oxygen_controller_voltage_channel?index=1",
             Kind::Ddoc(Ddoc::V2) => "digital_disolved_oxygen_controller_voltage_channel?index=2",
             Kind::Dtec => "digital_temperature_controller.hover",
+            Kind::Generic => "generic_topic.hover",
         }
     }
 }
@@ -156,6 +203,21 @@ impl Pane {
         response = response
             .on_hover_text(format!("{:x}", self.frame.hash))
             .on_hover_cursor(CursorIcon::Grab);
+        // Stale streams
+        if let Some(topic) = self.topic() {
+            let watchdog = mqtt::watchdog(ui.ctx());
+            let watchdog = watchdog.lock();
+            let mut stale = watchdog.stale(topic).peekable();
+            if stale.peek().is_some() {
+                ui.heading(RichText::new(WARNING).color(ui.visuals().error_fg_color))
+                    .on_hover_ui(|ui| {
+                        ui.label(ui.localize("staleness__stale"));
+                        for (identifier, stream) in stale {
+                            stream.show(ui, identifier);
+                        }
+                    });
+            }
+        }
         ui.separator();
         // View
         ui.add(ViewWidget::new(&mut self.view));
@@ -182,6 +244,23 @@ impl Pane {
             RichText::new(GEAR).heading(),
         )
         .on_hover_localized("settings");
+        // Metadata
+        ui.menu_button(RichText::new(INFO).heading(), |ui| {
+            ScrollArea::vertical().show(ui, |ui| {
+                MetadataWidget::new(&self.frame.meta).show(ui);
+            });
+            ui.separator();
+            Grid::new(ui.next_auto_id()).show(ui, |ui| {
+                ui.label(ui.localize("metadata__rows"));
+                ui.label(self.frame.data.height().to_string());
+                ui.end_row();
+                ui.label(ui.localize("metadata__columns"));
+                ui.label(self.frame.data.width().to_string());
+                ui.end_row();
+            });
+        })
+        .response
+        .on_hover_localized("metadata");
         ui.separator();
         // Export
         ui.menu_button(RichText::new(FLOPPY_DISK).heading(), |ui| {
@@ -196,7 +275,12 @@ impl Pane {
                         hash: hash(key),
                     }
                 });
-                xlsx::save(&data_frame, "data_frame.xlsx").ok();
+                xlsx::save(&data_frame, &self.frame.meta, "data_frame.xlsx").ok();
+                ui.close_menu();
+            }
+            // The source frame, so the file can be dropped back in
+            if ui.button("Arrow IPC").clicked() {
+                ipc::save(&self.frame.data, &self.frame.meta, "data_frame.arrow").ok();
                 ui.close_menu();
             }
         })
@@ -209,32 +293,27 @@ impl Pane {
     // https://github.com/rerun-io/egui_tiles/blob/1be4183f7c76cc96cadd8b0367f84c48a8e1b4bd/src/container/tabs.rs#L57
     // https://github.com/emilk/egui/discussions/3468
     pub(crate) fn body(&mut self, ui: &mut Ui) {
-        // let Some(ref data_frame) = self.data_frame.clone().or_else(|| {
-        //     let topic = self.topic()?;
-        //     let store = ui.data(|data| data.get_temp::<Arc<InMemory>>(Id::new(topic)))?;
-        //     let path = Path::from(topic.to_owned());
-        //     tokio::spawn(async move {
-        //         let result = store.get(&path).await?;
-        //         let bytes = result.bytes().await?;
-        //         let mut reader = ParquetReader::new(Cursor::new(bytes));
-        //         let meta = reader.get_metadata()?;
-        //         let data = reader.finish()?;
-        //         print!("data: {data:?}");
-        //         Ok::<_, Error>(())
-        //     });
-        //     // panic!("!!!");
-        //     // Some(())
-        // }) else {
-        //     ui.centered_and_justified(|ui| ui.spinner());
-        //     return;
-        // };
-
-        // ui.centered_and_justified(|ui| ui.spinner());
-        // return;
+        if self.is_real_time() {
+            self.update(ui.ctx()).ok();
+            if self.frame.data.is_empty() {
+                ui.centered_and_justified(|ui| ui.spinner());
+                return;
+            }
+        }
         self.windows(ui);
         match self.view {
+            View::Dashboard => {
+                DashboardView::new(
+                    self.source(),
+                    self.is_real_time(),
+                    self.kind.unit(),
+                    &self.frame,
+                    &self.settings,
+                )
+                .show(ui);
+            }
             View::Plot => {
-                PlotView::new(&self.frame, &mut self.settings).show(ui);
+                PlotView::new(self.source(), &self.frame, &mut self.settings).show(ui);
             }
             View::Table => {
                 let data_frame = ui.memory_mut(|memory| {
@@ -252,10 +331,34 @@ impl Pane {
         }
     }
 
+    /// Reads the topic buffer filled by the MQTT handler
+    #[instrument(skip(self, ctx), err)]
+    fn update(&mut self, ctx: &Context) -> Result<()> {
+        let Some(topic) = self.topic() else {
+            return Ok(());
+        };
+        let Some(buffer) = mqtt::buffer(ctx, topic) else {
+            return Ok(());
+        };
+        let mut buffer = buffer.lock();
+        if buffer.is_empty() || self.version == Some(buffer.version()) {
+            return Ok(());
+        }
+        let mut data = buffer.data_frame()?;
+        if self.kind == Kind::Generic {
+            data = infer(&data)?;
+        }
+        let meta = meta(&data)?;
+        self.frame = Hashed::new(MetaDataFrame::new(meta, data));
+        self.version = Some(buffer.version());
+        Ok(())
+    }
+
     fn windows(&mut self, ui: &mut Ui) {
         // Settings
         let mut open_settings_window = self.state.open_settings_window;
         let title = match self.view {
+            View::Dashboard => format!("{GEAR} Dashboard settings"),
             View::Plot => format!("{GEAR} Plot settings"),
             View::Table => format!("{GEAR} Table settings"),
         };
@@ -267,20 +370,44 @@ impl Pane {
                 self.settings.show(ui);
                 ui.separator();
                 match self.view {
-                    View::Plot => self.settings.plot.show(ui),
-                    View::Table => self.settings.table.show(ui, &self.frame.data.clone()),
+                    View::Dashboard => {
+                        self.settings.values.show(ui, &self.frame.data);
+                        self.settings.dashboard.show(ui);
+                    }
+                    View::Plot => {
+                        self.settings.values.show(ui, &self.frame.data);
+                        self.settings.plot.show(ui);
+                    }
+                    View::Table => {
+                        self.settings.values.show(ui, &self.frame.data);
+                        self.settings
+                            .table
+                            .show(ui, &self.frame.data, &self.settings.values);
+                    }
                 }
             });
         self.state.open_settings_window = open_settings_window;
     }
 }
 
+/// Selects the identifier, timestamp and the numeric value columns
+fn infer(data: &DataFrame) -> Result<DataFrame> {
+    let mut names = vec![IDENTIFIER, TIMESTAMP];
+    names.extend(values(data).map(|column| column.name().as_str()));
+    if names.len() == 2 {
+        bail!("No numeric value column");
+    }
+    Ok(data.select(names)?)
+}
+
 /// Kind
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub(crate) enum Kind {
     Atuc,
     Ddoc(Ddoc),
     Dtec,
+    /// Discovered topic, the value column is inferred from the schema
+    Generic,
 }
 
 /// Digital Disolved Oxygen Controller
@@ -295,7 +422,45 @@ pub(crate) enum Ddoc {
 }
 
 impl Kind {
-    pub(crate) const fn topic(&self) -> &str {
+    /// Device kind by the value column name
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        [
+            (NAME_TEMPERATURE, Kind::Dtec),
+            (NAME_TURBIDITY, Kind::Atuc),
+            (NAME_DDOC_C1, Kind::Ddoc(Ddoc::C1)),
+            (NAME_DDOC_C2, Kind::Ddoc(Ddoc::C2)),
+            (NAME_DDOC_T1, Kind::Ddoc(Ddoc::T1)),
+            (NAME_DDOC_T2, Kind::Ddoc(Ddoc::T2)),
+            (NAME_DDOC_V1, Kind::Ddoc(Ddoc::V1)),
+            (NAME_DDOC_V2, Kind::Ddoc(Ddoc::V2)),
+        ]
+        .into_iter()
+        .find_map(|(candidate, kind)| candidate.eq_ignore_ascii_case(name).then_some(kind))
+    }
+
+    pub(crate) const fn icon(&self) -> &'static str {
+        match self {
+            Kind::Atuc => DROP_HALF,
+            Kind::Ddoc(Ddoc::C1 | Ddoc::C2) => LIGHTNING,
+            Kind::Ddoc(Ddoc::T1 | Ddoc::T2) => THERMOMETER,
+            Kind::Ddoc(Ddoc::V1 | Ddoc::V2) => DROP,
+            Kind::Dtec => THERMOMETER,
+            Kind::Generic => BROADCAST,
+        }
+    }
+
+    /// Unit of the value, empty if unknown
+    pub(crate) const fn unit(&self) -> &'static str {
+        match self {
+            Kind::Ddoc(Ddoc::C1 | Ddoc::C2) => "mA",
+            Kind::Ddoc(Ddoc::T1 | Ddoc::T2) | Kind::Dtec => "°C",
+            Kind::Ddoc(Ddoc::V1) => "mg/L",
+            Kind::Ddoc(Ddoc::V2) => "%",
+            Kind::Atuc | Kind::Generic => "",
+        }
+    }
+
+    pub(crate) const fn topic(&self) -> &'static str {
         match self {
             Kind::Atuc => TOPIC_ATUC,
             Kind::Ddoc(Ddoc::C1) => TOPIC_DDOC_C1,
@@ -305,11 +470,14 @@ impl Kind {
             Kind::Ddoc(Ddoc::V1) => TOPIC_DDOC_V1,
             Kind::Ddoc(Ddoc::V2) => TOPIC_DDOC_V2,
             Kind::Dtec => TOPIC_DTEC,
+            // Generic panes carry their own topic
+            Kind::Generic => "",
         }
     }
 }
 
 pub(crate) mod behavior;
+pub(crate) mod dashboard;
 pub(crate) mod plot;
 pub(crate) mod settings;
 pub(crate) mod state;
@@ -1,16 +1,18 @@
 use super::Settings;
 use crate::{
     app::{
+        alarms,
         computers::{PlotComputed, PlotKey},
         metadata::MetaDataFrame,
+        recipe,
     },
     utils::hashed::Hashed,
 };
 use arrow::temporal_conversions::timestamp_ms_to_datetime;
 use chrono::{DateTime, Duration, DurationRound as _, Local, SubsecRound, TimeZone, Timelike, Utc};
-use egui::{Id, TextStyle, Ui, emath::round_to_decimals};
+use egui::{Color32, Id, TextStyle, Ui, emath::round_to_decimals};
 use egui_l20n::UiExt;
-use egui_plot::{GridInput, GridMark, Legend, Line, Plot, PlotPoints, Points};
+use egui_plot::{GridInput, GridMark, Legend, Line, LineStyle, Plot, PlotPoints, Points, Polygon};
 use std::fmt::Display;
 use tracing::trace;
 
@@ -30,18 +32,43 @@ static S: &str = "%S";
 /// Plot view
 #[derive(Debug, PartialEq)]
 pub(crate) struct View<'a> {
+    /// Topic relative to the prefix
+    pub(crate) topic: &'a str,
     pub(crate) frame: &'a Hashed<MetaDataFrame>,
     pub(crate) settings: &'a mut Settings,
 }
 
 impl<'a> View<'a> {
-    pub(crate) const fn new(frame: &'a Hashed<MetaDataFrame>, settings: &'a mut Settings) -> Self {
-        Self { frame, settings }
+    pub(crate) const fn new(
+        topic: &'a str,
+        frame: &'a Hashed<MetaDataFrame>,
+        settings: &'a mut Settings,
+    ) -> Self {
+        Self {
+            topic,
+            frame,
+            settings,
+        }
     }
 }
 
 impl View<'_> {
     pub(crate) fn show(&mut self, ui: &mut Ui) {
+        // Planned profile of the running recipe
+        let profile = recipe::profile(ui.ctx()).filter(|profile| profile.topic == self.topic);
+        let label = ui.localize("recipe");
+        let bands = alarms::bands(ui.ctx());
+        let alarm = ui.localize("alarm");
+        let now = Utc::now().timestamp_millis();
+        // The value column is named only in a wide frame
+        let wide = self.settings.values.selected(&self.frame.data).len() > 1;
+        let series = |identifier: u64, column: &str| {
+            if wide {
+                format!("{identifier:x} {column}")
+            } else {
+                format!("{identifier:x}")
+            }
+        };
         // Plot
         let mut plot = Plot::new(ID_SOURCE);
         if self.settings.plot.legend {
@@ -77,58 +104,89 @@ impl View<'_> {
                     settings: self.settings,
                 })
             });
+            // Alarm bands over the value range
+            let (min, max) = target
+                .source
+                .iter()
+                .flat_map(|(_, points)| points)
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &[_, y]| {
+                    (min.min(y), max.max(y))
+                });
+            if min <= max {
+                for band in bands.iter().filter(|band| band.topic == self.topic) {
+                    let start = band.start as f64;
+                    let end = band.end.unwrap_or(now) as f64;
+                    ui.polygon(
+                        Polygon::new(
+                            alarm.clone(),
+                            PlotPoints::new(vec![
+                                [start, min],
+                                [end, min],
+                                [end, max],
+                                [start, max],
+                            ]),
+                        )
+                        .fill_color(Color32::RED.gamma_multiply(0.15)),
+                    );
+                }
+            }
             // Source
             if self.settings.plot.source.line {
-                for (identifier, points) in target.source {
+                for ((identifier, column), points) in target.source {
+                    let name = series(identifier, &column);
                     // Line
-                    ui.line(
-                        Line::new(format!("{identifier:x}"), PlotPoints::new(points.clone()))
-                            .name(format!("{identifier:x}")),
-                    );
+                    ui.line(Line::new(name.clone(), PlotPoints::new(points.clone())));
                     // Points
                     if self.settings.plot.source.points.radius > 0.0 {
                         ui.points(
-                            Points::new(format!("{identifier:x}"), PlotPoints::new(points))
+                            Points::new(name, PlotPoints::new(points))
                                 .color(self.settings.plot.source.points.color)
                                 .filled(self.settings.plot.source.points.filled)
-                                .radius(self.settings.plot.source.points.radius)
-                                .name(format!("{identifier:x}")),
+                                .radius(self.settings.plot.source.points.radius),
                         );
                     }
                 }
             }
             // Resampling mean
             if self.settings.plot.resampling.mean {
-                for (identifier, points) in target.resampling.mean {
-                    let line = Line::new(format!("{identifier:x}"), PlotPoints::new(points))
-                        .name(format!("{identifier:x}"));
+                for ((identifier, column), points) in target.resampling.mean {
+                    let name = series(identifier, &column);
+                    let line = Line::new(name, PlotPoints::new(points));
                     ui.line(line);
                 }
             }
             // Resampling median
             if self.settings.plot.resampling.median {
-                for (identifier, points) in target.resampling.median {
-                    let line = Line::new(format!("{identifier:x}"), PlotPoints::new(points))
-                        .name(format!("{identifier:x}"));
+                for ((identifier, column), points) in target.resampling.median {
+                    let name = series(identifier, &column);
+                    let line = Line::new(name, PlotPoints::new(points));
                     ui.line(line);
                 }
             }
             // Rolling mean
             if self.settings.plot.rolling.mean {
-                for (identifier, points) in target.rolling.mean {
-                    let line = Line::new(format!("{identifier:x}"), PlotPoints::new(points))
-                        .name(format!("{identifier:x}"));
+                for ((identifier, column), points) in target.rolling.mean {
+                    let name = series(identifier, &column);
+                    let line = Line::new(name, PlotPoints::new(points));
                     ui.line(line);
                 }
             }
             // Rolling median
             if self.settings.plot.rolling.median {
-                for (identifier, points) in target.rolling.median {
-                    let line = Line::new(format!("{identifier:x}"), PlotPoints::new(points))
-                        .name(format!("{identifier:x}"));
+                for ((identifier, column), points) in target.rolling.median {
+                    let name = series(identifier, &column);
+                    let line = Line::new(name, PlotPoints::new(points));
                     ui.line(line);
                 }
             }
+            // Recipe
+            if let Some(profile) = profile {
+                let name = format!("{:x} {label}", profile.identifier);
+                ui.line(
+                    Line::new(name, PlotPoints::new(profile.points))
+                        .style(LineStyle::dashed_loose()),
+                );
+            }
         });
     }
 }
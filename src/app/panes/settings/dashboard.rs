@@ -0,0 +1,64 @@
+use crate::app::MAX_PRECISION;
+use egui::{ComboBox, Grid, RichText, Slider, Ui};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use serde::{Deserialize, Serialize};
+
+/// Statistics windows, minutes
+const WINDOWS: [u64; 6] = [5, 15, 30, 60, 6 * 60, 24 * 60];
+
+/// Dashboard settings
+#[derive(Clone, Copy, Debug, Deserialize, Hash, PartialEq, Serialize)]
+pub(crate) struct Settings {
+    /// Window of the min, max and mean before the current time of live panes or
+    /// the latest timestamp, minutes
+    pub(crate) window: u64,
+    pub(crate) precision: usize,
+}
+
+impl Settings {
+    pub(crate) const fn new() -> Self {
+        Self {
+            window: 15,
+            precision: 2,
+        }
+    }
+}
+
+impl Settings {
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        ui.collapsing(RichText::new(ui.localize("dashboard")).heading(), |ui| {
+            Grid::new(ui.next_auto_id()).show(ui, |ui| {
+                // Window
+                ui.label(ui.localize("dashboard__window"));
+                ComboBox::from_id_salt("DashboardWindow")
+                    .selected_text(minutes(ui, self.window))
+                    .show_ui(ui, |ui| {
+                        for window in WINDOWS {
+                            ui.selectable_value(&mut self.window, window, minutes(ui, window));
+                        }
+                    })
+                    .response
+                    .on_hover_localized("dashboard__window.hover");
+                ui.end_row();
+                // Precision
+                ui.label(ui.localize("precision"));
+                ui.add(Slider::new(&mut self.precision, 0..=MAX_PRECISION));
+                ui.end_row();
+            });
+        });
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn minutes(ui: &Ui, minutes: u64) -> String {
+    if minutes % 60 == 0 {
+        ui.localize(&format!("dashboard__hours?count={}", minutes / 60))
+    } else {
+        ui.localize(&format!("dashboard__minutes?count={minutes}"))
+    }
+}
@@ -1,6 +1,9 @@
 pub(crate) use self::table::{Order, Sort};
 
-use self::{plot::Settings as PlotSettings, table::Settings as TableSettings};
+use self::{
+    dashboard::Settings as DashboardSettings, plot::Settings as PlotSettings,
+    table::Settings as TableSettings, values::Settings as ValuesSettings,
+};
 use arrow::temporal_conversions::timestamp_ms_to_datetime;
 use chrono::{DateTime, FixedOffset, Local, Offset as _, TimeZone as _, Utc};
 use egui::{ComboBox, Grid, Ui};
@@ -13,6 +16,10 @@ pub(crate) struct Settings {
     pub(crate) time_zone: TimeZone,
     pub(crate) plot: PlotSettings,
     pub(crate) table: TableSettings,
+    #[serde(default)]
+    pub(crate) dashboard: DashboardSettings,
+    #[serde(default)]
+    pub(crate) values: ValuesSettings,
 }
 
 impl Settings {
@@ -21,6 +28,8 @@ impl Settings {
             time_zone: TimeZone::Local,
             plot: PlotSettings::new(),
             table: TableSettings::new(),
+            dashboard: DashboardSettings::new(),
+            values: ValuesSettings::new(),
         }
     }
 }
@@ -156,5 +165,7 @@ impl TimeZone {
     }
 }
 
+mod dashboard;
 mod plot;
 mod table;
+mod values;
@@ -1,8 +1,9 @@
-use crate::app::MAX_PRECISION;
-use egui::{ComboBox, Grid, PopupCloseBehavior, RichText, Slider, Ui};
+use super::ValuesSettings;
+use crate::app::{MAX_PRECISION, values};
+use egui::{Button, ComboBox, Grid, PopupCloseBehavior, RichText, Slider, Ui};
 use egui_ext::LabeledSeparator as _;
 use egui_l20n::{ResponseExt, UiExt as _};
-use egui_phosphor::regular::{FUNNEL, FUNNEL_X};
+use egui_phosphor::regular::{ARROW_DOWN, ARROW_UP, FUNNEL, FUNNEL_X};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -20,6 +21,8 @@ pub(crate) struct Settings {
     pub(crate) sticky_columns: usize,
     pub(crate) truncate: bool,
 
+    #[serde(default)]
+    pub(crate) columns: Columns,
     pub(crate) filter: Filter,
     pub(crate) sort: Sort,
     pub(crate) order: Order,
@@ -34,13 +37,14 @@ impl Settings {
             sticky_columns: 0,
             truncate: false,
 
+            columns: Columns::new(),
             filter: Filter::new(),
             sort: Sort::Timestamp,
             order: Order::Ascending,
         }
     }
 
-    pub(crate) fn show(&mut self, ui: &mut Ui, data_frame: &DataFrame) {
+    pub(crate) fn show(&mut self, ui: &mut Ui, data_frame: &DataFrame, values: &ValuesSettings) {
         Grid::new("TableSettings").show(ui, |ui| -> PolarsResult<()> {
             // Precision
             ui.label(ui.localize("precision"));
@@ -56,6 +60,13 @@ impl Settings {
             ui.separator();
             ui.end_row();
 
+            // Columns
+            ui.separator();
+            ui.labeled_separator(RichText::new(ui.localize("columns")).heading());
+            ui.end_row();
+
+            self.columns.show(ui, data_frame, values);
+
             // Filter
             ui.separator();
             ui.labeled_separator(RichText::new(ui.localize("filter")).heading());
@@ -127,6 +138,116 @@ impl Default for Settings {
     }
 }
 
+/// Columns
+#[derive(Clone, Debug, Default, Deserialize, Hash, PartialEq, Serialize)]
+pub(crate) struct Columns {
+    /// User order, the columns missing here follow in the frame order
+    pub(crate) order: Vec<String>,
+    pub(crate) hidden: BTreeSet<String>,
+}
+
+impl Columns {
+    pub(crate) const fn new() -> Self {
+        Self {
+            order: Vec::new(),
+            hidden: BTreeSet::new(),
+        }
+    }
+
+    /// Column names of the frame in the user order, without the value columns
+    /// left out in the value settings
+    pub(crate) fn ordered(
+        &self,
+        data_frame: &DataFrame,
+        values: &ValuesSettings,
+    ) -> Vec<PlSmallStr> {
+        let unselected = Self::unselected(data_frame, values);
+        let names = data_frame
+            .get_columns()
+            .iter()
+            .map(|column| column.name())
+            .filter(|name| !unselected.contains(name));
+        let mut ordered = self
+            .order
+            .iter()
+            .filter_map(|name| {
+                names
+                    .clone()
+                    .find(|candidate| candidate.as_str() == name.as_str())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in names {
+            if !ordered.contains(name) {
+                ordered.push(name.clone());
+            }
+        }
+        ordered
+    }
+
+    /// Shown column names in the user order
+    pub(crate) fn visible(
+        &self,
+        data_frame: &DataFrame,
+        values: &ValuesSettings,
+    ) -> Vec<PlSmallStr> {
+        let mut ordered = self.ordered(data_frame, values);
+        ordered.retain(|name| !self.hidden.contains(name.as_str()));
+        ordered
+    }
+
+    /// Value columns left out in the value settings
+    fn unselected(data_frame: &DataFrame, settings: &ValuesSettings) -> Vec<PlSmallStr> {
+        let selected = settings.selected(data_frame);
+        values(data_frame)
+            .map(|column| column.name())
+            .filter(|name| !selected.contains(name))
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn show(&mut self, ui: &mut Ui, data_frame: &DataFrame, values: &ValuesSettings) {
+        let ordered = self.ordered(data_frame, values);
+        let mut swap = None;
+        for (index, name) in ordered.iter().enumerate() {
+            let mut visible = !self.hidden.contains(name.as_str());
+            if ui
+                .checkbox(&mut visible, name.as_str())
+                .on_hover_localized("columns__visible.hover")
+                .changed()
+            {
+                if visible {
+                    self.hidden.remove(name.as_str());
+                } else {
+                    self.hidden.insert(name.to_string());
+                }
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(index > 0, Button::new(ARROW_UP))
+                    .on_hover_localized("columns__up.hover")
+                    .clicked()
+                {
+                    swap = Some(index - 1);
+                }
+                if ui
+                    .add_enabled(index + 1 < ordered.len(), Button::new(ARROW_DOWN))
+                    .on_hover_localized("columns__down.hover")
+                    .clicked()
+                {
+                    swap = Some(index);
+                }
+            });
+            ui.end_row();
+        }
+        if let Some(index) = swap {
+            let mut ordered = ordered;
+            ordered.swap(index, index + 1);
+            self.order = ordered.iter().map(ToString::to_string).collect();
+        }
+    }
+}
+
 /// Filter
 #[derive(Clone, Debug, Default, Deserialize, Hash, PartialEq, Serialize)]
 pub(crate) struct Filter {
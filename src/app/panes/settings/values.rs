@@ -0,0 +1,55 @@
+use crate::app::values;
+use egui::{RichText, Ui};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Value columns settings
+#[derive(Clone, Debug, Default, Deserialize, Hash, PartialEq, Serialize)]
+pub(crate) struct Settings {
+    /// Value columns left out of the plot, new columns are shown
+    pub(crate) hidden: BTreeSet<String>,
+}
+
+impl Settings {
+    pub(crate) const fn new() -> Self {
+        Self {
+            hidden: BTreeSet::new(),
+        }
+    }
+
+    /// Names of the shown value columns, in the frame order
+    pub(crate) fn selected(&self, data_frame: &DataFrame) -> Vec<PlSmallStr> {
+        values(data_frame)
+            .filter(|column| !self.hidden.contains(column.name().as_str()))
+            .map(|column| column.name().clone())
+            .collect()
+    }
+}
+
+impl Settings {
+    pub(crate) fn show(&mut self, ui: &mut Ui, data_frame: &DataFrame) {
+        // Nothing to choose in a narrow frame
+        if values(data_frame).nth(1).is_none() {
+            return;
+        }
+        ui.collapsing(RichText::new(ui.localize("values")).heading(), |ui| {
+            for column in values(data_frame) {
+                let name = column.name().as_str();
+                let mut checked = !self.hidden.contains(name);
+                if ui
+                    .checkbox(&mut checked, name)
+                    .on_hover_localized("values.hover")
+                    .changed()
+                {
+                    if checked {
+                        self.hidden.remove(name);
+                    } else {
+                        self.hidden.insert(name.to_owned());
+                    }
+                }
+            }
+        });
+    }
+}
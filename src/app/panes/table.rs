@@ -1,19 +1,17 @@
 use super::{ID_SOURCE, settings::Settings, state::State};
-use crate::app::{NAME_TEMPERATURE, NAME_TURBIDITY, YMDHMS};
+use crate::app::YMDHMS;
 use egui::{Context, Frame, Id, Margin, RichText, TextStyle, TextWrapMode, Ui, Vec2, vec2};
 use egui_l20n::{ResponseExt, UiExt as _};
-use egui_phosphor::regular::HASH;
+use egui_phosphor::regular::{CHECK, HASH, X};
 use egui_table::{CellInfo, Column, HeaderCellInfo, HeaderRow, Table, TableDelegate, TableState};
 use polars::prelude::*;
-use tracing::{error, instrument};
+use tracing::instrument;
 
 const MARGIN: Vec2 = vec2(4.0, 2.0);
 
 const INDEX: usize = 0;
-const IDENTIFIER: usize = 1;
-const TIMESTAMP: usize = 2;
-const VALUE: usize = 3;
-const LEN: usize = 4;
+const IDENTIFIER: &str = "Identifier";
+const TIMESTAMP: &str = "Timestamp";
 
 /// Table view
 #[derive(Debug)]
@@ -47,7 +45,8 @@ impl View<'_> {
         }
         let height = ui.text_style_height(&TextStyle::Heading) + 2.0 * MARGIN.y;
         let num_rows = self.data_frame.height() as u64;
-        let num_columns = LEN;
+        // Index and the frame columns
+        let num_columns = self.data_frame.width() + 1;
         Table::new()
             .id_salt(id_salt)
             .num_rows(num_rows)
@@ -66,68 +65,75 @@ impl View<'_> {
         if self.settings.table.truncate {
             ui.style_mut().wrap_mode = Some(TextWrapMode::Truncate);
         }
-        match (row, column) {
-            (0, INDEX) => {
-                ui.heading(HASH).on_hover_localized("index.hover");
-            }
-            (0, IDENTIFIER) => {
+        if row != 0 {
+            return;
+        }
+        if column == INDEX {
+            ui.heading(HASH).on_hover_localized("index.hover");
+            return;
+        }
+        // Shifted by the index column
+        let Some(data) = self.data_frame.get_columns().get(column - 1) else {
+            return;
+        };
+        match data.name().as_str() {
+            IDENTIFIER => {
                 ui.heading(ui.localize("identifier"))
                     .on_hover_localized("identifier.hover");
             }
-            (0, TIMESTAMP) => {
+            TIMESTAMP => {
                 ui.heading(ui.localize("timestamp"))
                     .on_hover_localized("timestamp.hover");
             }
-            (0, VALUE) => {
-                ui.heading(ui.localize("value"))
-                    .on_hover_localized("value.hover");
+            name => {
+                ui.heading(name).on_hover_text(data.dtype().to_string());
             }
-            _ => {}
-        };
+        }
     }
 
     #[instrument(skip(ui), err)]
     fn cell_content_ui(&mut self, ui: &mut Ui, row: usize, column: usize) -> PolarsResult<()> {
-        match (row, column) {
-            (row, INDEX) => {
-                ui.label(row.to_string());
+        if column == INDEX {
+            ui.label(row.to_string());
+            return Ok(());
+        }
+        let data = &self.data_frame[column - 1];
+        match data.get(row)? {
+            AnyValue::Null => {}
+            AnyValue::UInt64(identifier) if data.name().as_str() == IDENTIFIER => {
+                ui.label(RichText::new(format!("{identifier:x}")).monospace());
+            }
+            AnyValue::Float32(value) => {
+                ui.label(self.float(value as _));
+            }
+            AnyValue::Float64(value) => {
+                ui.label(self.float(value));
             }
-            (row, IDENTIFIER) => {
-                let identifier = self.data_frame["Identifier"].u64()?;
-                if let Some(identifier) = identifier.get(row) {
-                    ui.label(RichText::new(format!("{identifier:x}")).monospace());
-                }
+            AnyValue::Datetime(value, time_unit, _) => {
+                let milliseconds = match time_unit {
+                    TimeUnit::Nanoseconds => value / 1_000_000,
+                    TimeUnit::Microseconds => value / 1_000,
+                    TimeUnit::Milliseconds => value,
+                };
+                ui.label(self.settings.time_zone.format_time(milliseconds, YMDHMS));
             }
-            (row, TIMESTAMP) => {
-                let timestamp = self.data_frame["Timestamp"].datetime()?;
-                if let Some(timestamp) = timestamp.get(row) {
-                    ui.label(self.settings.time_zone.format_time(timestamp, YMDHMS));
-                }
+            AnyValue::Boolean(value) => {
+                ui.label(if value { CHECK } else { X });
             }
-            (row, VALUE) => {
-                let last = self.data_frame.width() - 1;
-                match &*self.data_frame[last].name().to_lowercase() {
-                    NAME_TEMPERATURE => {
-                        let temperature = self.data_frame[last].f32()?;
-                        if let Some(temperature) = temperature.get(row) {
-                            ui.label(temperature.to_string());
-                        }
-                    }
-                    NAME_TURBIDITY => {
-                        let turbidity = self.data_frame[last].u16()?;
-                        if let Some(turbidity) = turbidity.get(row) {
-                            ui.label(turbidity.to_string());
-                        }
-                    }
-                    name => {
-                        error!("Unsupported name: {name}");
-                    }
-                }
+            AnyValue::String(value) => {
+                ui.label(value);
+            }
+            value => {
+                ui.label(value.to_string());
             }
-            _ => {}
         }
         Ok(())
     }
+
+    fn float(&self, value: f64) -> String {
+        let precision = self.settings.table.precision;
+        format!("{value:.precision$}")
+    }
 }
 
 impl TableDelegate for View<'_> {
@@ -1,11 +1,12 @@
 use egui::{Response, RichText, Ui, Widget};
 use egui_l20n::{ResponseExt as _, UiExt as _};
-use egui_phosphor::regular::{CHART_LINE, TABLE};
+use egui_phosphor::regular::{CHART_LINE, GAUGE, TABLE};
 use serde::{Deserialize, Serialize};
 
 /// View
 #[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub(crate) enum View {
+    Dashboard,
     Plot,
     #[default]
     Table,
@@ -14,6 +15,7 @@ pub(crate) enum View {
 impl View {
     pub(crate) fn text(&self) -> &'static str {
         match self {
+            Self::Dashboard => "dashboard",
             Self::Plot => "plot",
             Self::Table => "table",
         }
@@ -21,6 +23,7 @@ impl View {
 
     pub(crate) fn hover_text(&self) -> &'static str {
         match self {
+            Self::Dashboard => "dashboard.hover",
             Self::Plot => "plot.hover",
             Self::Table => "table.hover",
         }
@@ -30,6 +33,7 @@ impl View {
 impl View {
     pub(crate) const fn icon(&self) -> &str {
         match self {
+            Self::Dashboard => GAUGE,
             Self::Plot => CHART_LINE,
             Self::Table => TABLE,
         }
@@ -65,6 +69,17 @@ impl Widget for ViewWidget<'_> {
                     format!("{} {}", View::Plot.icon(), ui.localize(View::Plot.text())),
                 )
                 .on_hover_localized(View::Plot.hover_text());
+            response |= ui
+                .selectable_value(
+                    self.view,
+                    View::Dashboard,
+                    format!(
+                        "{} {}",
+                        View::Dashboard.icon(),
+                        ui.localize(View::Dashboard.text()),
+                    ),
+                )
+                .on_hover_localized(View::Dashboard.hover_text());
             if response.changed() {
                 ui.close_menu();
             }
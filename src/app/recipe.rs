@@ -0,0 +1,557 @@
+use super::{
+    YMDHMS,
+    control::{Action, Command, Control},
+    mqtt::{Connection, Settings},
+};
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use egui::{Button, ComboBox, Context, DragValue, Grid, Id, Modal, RichText, Ui, Window};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::{
+    ARROW_DOWN, ARROW_UP, CHART_LINE_UP, MINUS, PLAY, PLUS, STOP, TRASH, WARNING, X,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+
+const PROFILE: &str = "Profile";
+const MILLISECONDS_PER_MINUTE: f64 = 60_000.0;
+/// Smallest setpoint change worth publishing
+const RESOLUTION: f64 = 0.1;
+const PERIOD: TimeDelta = TimeDelta::seconds(1);
+
+/// Recipes
+///
+/// Time based setpoint profiles of the device controllers and the runner which
+/// publishes the scheduled setpoints through the control.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct Recipes {
+    pub(crate) recipes: Vec<Recipe>,
+    pub(crate) selected: usize,
+    #[serde(skip)]
+    pub(crate) open: bool,
+    #[serde(skip)]
+    run: Option<Run>,
+    /// The selected recipe waits for the confirmation
+    #[serde(skip)]
+    confirmation: bool,
+}
+
+impl Recipes {
+    pub(crate) fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    pub(crate) fn start(&mut self, context: &Context) {
+        let Some(recipe) = self.recipes.get(self.selected) else {
+            return;
+        };
+        info!(name = %recipe.name, "Start recipe");
+        let start = Utc::now();
+        let offset = start.timestamp_millis() as f64;
+        context.data_mut(|data| {
+            data.insert_temp(
+                Id::new(PROFILE),
+                Profile {
+                    topic: recipe.action.kind().topic().to_owned(),
+                    identifier: recipe.identifier,
+                    points: recipe
+                        .points()
+                        .into_iter()
+                        .map(|[x, y]| [x + offset, y])
+                        .collect(),
+                },
+            )
+        });
+        self.run = Some(Run {
+            recipe: recipe.clone(),
+            start,
+            value: None,
+            attempted: None,
+            error: None,
+        });
+    }
+
+    pub(crate) fn stop(&mut self, context: &Context) {
+        if let Some(run) = self.run.take() {
+            info!(name = %run.recipe.name, "Stop recipe");
+        }
+        context.data_mut(|data| data.remove::<Profile>(Id::new(PROFILE)));
+    }
+
+    /// Publishes the scheduled setpoint when it changes, a failed setpoint is
+    /// retried every period
+    pub(crate) fn update(
+        &mut self,
+        context: &Context,
+        control: &mut Control,
+        connection: &mut Connection,
+        settings: &Settings,
+    ) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        let now = Utc::now();
+        let elapsed = (now - run.start).num_milliseconds() as f64;
+        let finished = elapsed >= run.recipe.duration();
+        let value = run.recipe.value(elapsed);
+        let changed = run
+            .value
+            .is_none_or(|last| (value - last).abs() >= RESOLUTION);
+        let due = run
+            .attempted
+            .is_none_or(|attempted| now - attempted >= PERIOD);
+        if changed && (due || finished && run.error.is_none()) {
+            run.attempted = Some(now);
+            let result = control.send(
+                context,
+                connection,
+                settings,
+                Command {
+                    device: run.recipe.action.device(),
+                    identifier: run.recipe.identifier,
+                    action: run.recipe.action,
+                    value,
+                },
+                Some(&run.recipe.name),
+            );
+            match result {
+                Ok(()) => {
+                    run.value = Some(value);
+                    run.error = None;
+                }
+                Err(error) => run.error = Some(error.to_string()),
+            }
+        }
+        // The last setpoint is published before the run stops
+        if finished && run.error.is_none() {
+            self.stop(context);
+        } else {
+            context.request_repaint_after(Duration::from_secs(1));
+        }
+    }
+}
+
+impl Recipes {
+    pub(crate) fn window(&mut self, context: &Context) {
+        let mut open = self.open;
+        Window::new(format!("{CHART_LINE_UP} Recipes"))
+            .open(&mut open)
+            .show(context, |ui| self.show(ui));
+        self.open = open;
+        // Confirmation, once for the whole run
+        if !self.confirmation {
+            return;
+        }
+        let Some(recipe) = self.recipes.get(self.selected) else {
+            self.confirmation = false;
+            return;
+        };
+        let mut confirmed = None;
+        Modal::new(Id::new("RecipeConfirmation")).show(context, |ui| {
+            ui.heading(ui.localize("recipe__confirm"));
+            recipe.steps(ui);
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .button(format!("{PLAY} {}", ui.localize("recipe__run")))
+                    .clicked()
+                {
+                    confirmed = Some(true);
+                }
+                if ui
+                    .button(format!("{X} {}", ui.localize("control__cancel")))
+                    .clicked()
+                {
+                    confirmed = Some(false);
+                }
+            });
+        });
+        if let Some(confirmed) = confirmed {
+            self.confirmation = false;
+            if confirmed {
+                self.start(context);
+            }
+        }
+    }
+
+    fn show(&mut self, ui: &mut Ui) {
+        // Selection
+        ui.horizontal(|ui| {
+            let text = self
+                .recipes
+                .get(self.selected)
+                .map_or_else(String::new, |recipe| recipe.name.clone());
+            ui.add_enabled_ui(!self.is_running(), |ui| {
+                ComboBox::from_id_salt("Recipe")
+                    .selected_text(text)
+                    .show_ui(ui, |ui| {
+                        for (index, recipe) in self.recipes.iter().enumerate() {
+                            ui.selectable_value(&mut self.selected, index, &recipe.name);
+                        }
+                    });
+                if ui.button(PLUS).on_hover_localized("recipe__add").clicked() {
+                    self.recipes.push(Recipe::new());
+                    self.selected = self.recipes.len() - 1;
+                }
+                if ui
+                    .button(TRASH)
+                    .on_hover_localized("recipe__delete")
+                    .clicked()
+                    && self.selected < self.recipes.len()
+                {
+                    self.recipes.remove(self.selected);
+                    self.selected = self.selected.saturating_sub(1);
+                }
+            });
+        });
+        ui.separator();
+        // Run
+        if let Some(run) = &self.run {
+            let elapsed = (Utc::now() - run.start).num_milliseconds() as f64;
+            let mut stop = false;
+            ui.horizontal(|ui| {
+                stop = ui
+                    .button(RichText::new(STOP).heading())
+                    .on_hover_localized("recipe__stop")
+                    .clicked();
+                ui.label(ui.localize(&format!("recipe__running?name={}", run.recipe.name)));
+            });
+            Grid::new(ui.next_auto_id()).show(ui, |ui| {
+                ui.label(ui.localize("recipe__started"));
+                ui.label(run.start.with_timezone(&Local).format(YMDHMS).to_string());
+                ui.end_row();
+                ui.label(ui.localize("recipe__elapsed"));
+                ui.label(format!(
+                    "{:.1} / {:.1} min",
+                    elapsed / MILLISECONDS_PER_MINUTE,
+                    run.recipe.duration() / MILLISECONDS_PER_MINUTE,
+                ));
+                ui.end_row();
+                ui.label(ui.localize("recipe__setpoint"));
+                ui.label(format!(
+                    "{:.1}{}",
+                    run.recipe.value(elapsed),
+                    run.recipe.action.unit(),
+                ));
+                ui.end_row();
+                if let Some(error) = &run.error {
+                    ui.label(ui.localize("recipe__failed"));
+                    ui.label(RichText::new(WARNING).color(ui.visuals().error_fg_color))
+                        .on_hover_text(error);
+                    ui.end_row();
+                }
+            });
+            if stop {
+                self.stop(ui.ctx());
+            }
+            return;
+        }
+        let Some(recipe) = self.recipes.get_mut(self.selected) else {
+            ui.label(ui.localize("recipe__none"));
+            return;
+        };
+        recipe.show(ui);
+        ui.separator();
+        if ui
+            .button(RichText::new(PLAY).heading())
+            .on_hover_localized("recipe__start")
+            .clicked()
+        {
+            self.confirmation = true;
+        }
+    }
+}
+
+/// Setpoint profile of a device controller
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Recipe {
+    pub(crate) name: String,
+    pub(crate) identifier: u64,
+    pub(crate) action: Action,
+    /// Value at the start
+    pub(crate) initial: f64,
+    pub(crate) stages: Vec<Stage>,
+}
+
+impl Recipe {
+    pub(crate) fn new() -> Self {
+        Self {
+            name: "Recipe".to_owned(),
+            identifier: 1,
+            action: Action::Setpoint,
+            initial: 30.0,
+            stages: vec![
+                Stage {
+                    kind: StageKind::Ramp,
+                    target: 37.0,
+                    duration: 120.0,
+                },
+                Stage {
+                    kind: StageKind::Hold,
+                    target: 37.0,
+                    duration: 60.0,
+                },
+            ],
+        }
+    }
+
+    /// Profile points: milliseconds from the start and value
+    pub(crate) fn points(&self) -> Vec<[f64; 2]> {
+        let mut points = vec![[0.0, self.initial]];
+        let mut time = 0.0;
+        let mut value = self.initial;
+        for stage in &self.stages {
+            let duration = stage.duration * MILLISECONDS_PER_MINUTE;
+            match stage.kind {
+                StageKind::Ramp => value = stage.target,
+                StageKind::Hold => {}
+                StageKind::Step => {
+                    value = stage.target;
+                    points.push([time, value]);
+                }
+            }
+            time += duration;
+            points.push([time, value]);
+        }
+        points
+    }
+
+    /// Duration, milliseconds
+    pub(crate) fn duration(&self) -> f64 {
+        self.stages
+            .iter()
+            .map(|stage| stage.duration * MILLISECONDS_PER_MINUTE)
+            .sum()
+    }
+
+    /// Scheduled value at the time from the start, milliseconds
+    pub(crate) fn value(&self, time: f64) -> f64 {
+        let points = self.points();
+        // The last point at or before the time, so a step takes effect at once
+        let index = points.partition_point(|&[x, _]| x <= time);
+        match (points.get(index.wrapping_sub(1)), points.get(index)) {
+            (Some(&[x0, y0]), Some(&[x1, y1])) if x1 > x0 => {
+                y0 + (y1 - y0) * (time - x0) / (x1 - x0)
+            }
+            (Some(&[_, y]), _) | (None, Some(&[_, y])) => y,
+            (None, None) => self.initial,
+        }
+    }
+}
+
+impl Recipe {
+    /// Read only summary of the published steps
+    fn steps(&self, ui: &mut Ui) {
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            ui.label(ui.localize("recipe__name"));
+            ui.label(&self.name);
+            ui.end_row();
+            ui.label(ui.localize("control__identifier"));
+            ui.label(format!("{:x}", self.identifier));
+            ui.end_row();
+            ui.label(ui.localize("control__command"));
+            ui.label(ui.localize(self.action.text()));
+            ui.end_row();
+            ui.label(ui.localize("recipe__initial"));
+            ui.label(format!("{}{}", self.initial, self.action.unit()));
+            ui.end_row();
+        });
+        ui.separator();
+        Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+            ui.label(ui.localize("recipe__stage"));
+            ui.label(ui.localize("recipe__target"));
+            ui.label(ui.localize("recipe__duration"));
+            ui.end_row();
+            for stage in &self.stages {
+                ui.label(ui.localize(stage.kind.text()));
+                if stage.kind == StageKind::Hold {
+                    ui.label("-");
+                } else {
+                    ui.label(format!("{}{}", stage.target, self.action.unit()));
+                }
+                ui.label(format!("{} min", stage.duration));
+                ui.end_row();
+            }
+        });
+    }
+
+    fn show(&mut self, ui: &mut Ui) {
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            // Name
+            ui.label(ui.localize("recipe__name"));
+            ui.text_edit_singleline(&mut self.name);
+            ui.end_row();
+            // Identifier
+            ui.label(ui.localize("control__identifier"))
+                .on_hover_localized("control__identifier.hover");
+            ui.add(DragValue::new(&mut self.identifier).hexadecimal(1, false, false));
+            ui.end_row();
+            // Command
+            ui.label(ui.localize("control__command"))
+                .on_hover_localized("control__command.hover");
+            ComboBox::from_id_salt("RecipeCommand")
+                .selected_text(ui.localize(self.action.text()))
+                .show_ui(ui, |ui| {
+                    for action in [Action::Setpoint, Action::Aeration] {
+                        ui.selectable_value(&mut self.action, action, ui.localize(action.text()))
+                            .on_hover_localized(action.hover_text());
+                    }
+                });
+            ui.end_row();
+            // Initial
+            ui.label(ui.localize("recipe__initial"))
+                .on_hover_localized("recipe__initial.hover");
+            ui.add(
+                DragValue::new(&mut self.initial)
+                    .speed(0.1)
+                    .suffix(self.action.unit()),
+            );
+            ui.end_row();
+        });
+        ui.separator();
+        // Stages
+        let mut swap = None;
+        let mut remove = None;
+        let count = self.stages.len();
+        Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+            ui.label(ui.localize("recipe__stage"));
+            ui.label(ui.localize("recipe__target"));
+            ui.label(ui.localize("recipe__duration"));
+            ui.end_row();
+            for (index, stage) in self.stages.iter_mut().enumerate() {
+                ComboBox::from_id_salt(ui.id().with(index))
+                    .selected_text(ui.localize(stage.kind.text()))
+                    .show_ui(ui, |ui| {
+                        for kind in [StageKind::Ramp, StageKind::Hold, StageKind::Step] {
+                            ui.selectable_value(&mut stage.kind, kind, ui.localize(kind.text()))
+                                .on_hover_localized(kind.hover_text());
+                        }
+                    })
+                    .response
+                    .on_hover_localized(stage.kind.hover_text());
+                ui.add_enabled(
+                    stage.kind != StageKind::Hold,
+                    DragValue::new(&mut stage.target)
+                        .speed(0.1)
+                        .suffix(self.action.unit()),
+                );
+                ui.add(
+                    DragValue::new(&mut stage.duration)
+                        .range(0.0..=f64::MAX)
+                        .suffix(" min"),
+                );
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(index > 0, Button::new(ARROW_UP)).clicked() {
+                        swap = Some(index - 1);
+                    }
+                    if ui
+                        .add_enabled(index + 1 < count, Button::new(ARROW_DOWN))
+                        .clicked()
+                    {
+                        swap = Some(index);
+                    }
+                    if ui.button(MINUS).clicked() {
+                        remove = Some(index);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if let Some(index) = swap {
+            self.stages.swap(index, index + 1);
+        }
+        if let Some(index) = remove {
+            self.stages.remove(index);
+        }
+        if ui
+            .button(PLUS)
+            .on_hover_localized("recipe__add_stage")
+            .clicked()
+        {
+            let target = self
+                .stages
+                .last()
+                .map_or(self.initial, |stage| stage.target);
+            self.stages.push(Stage {
+                kind: StageKind::Hold,
+                target,
+                duration: 60.0,
+            });
+        }
+    }
+}
+
+impl Default for Recipe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recipe stage
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct Stage {
+    pub(crate) kind: StageKind,
+    /// Value at the end of the stage, ignored by the hold
+    pub(crate) target: f64,
+    /// Minutes
+    pub(crate) duration: f64,
+}
+
+/// Stage kind
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum StageKind {
+    /// Linear change to the target over the duration
+    Ramp,
+    /// Keeps the value for the duration
+    Hold,
+    /// Jumps to the target and keeps it for the duration
+    Step,
+}
+
+impl StageKind {
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::Ramp => "stage__ramp",
+            Self::Hold => "stage__hold",
+            Self::Step => "stage__step",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::Ramp => "stage__ramp.hover",
+            Self::Hold => "stage__hold.hover",
+            Self::Step => "stage__step.hover",
+        }
+    }
+}
+
+/// Running recipe
+#[derive(Debug)]
+struct Run {
+    recipe: Recipe,
+    start: DateTime<Utc>,
+    /// Last published value
+    value: Option<f64>,
+    /// Last publish attempt
+    attempted: Option<DateTime<Utc>>,
+    /// Error of the last attempt, the setpoint is retried
+    error: Option<String>,
+}
+
+/// Planned profile of the running recipe, drawn on the matching plot pane
+#[derive(Clone, Debug)]
+pub(crate) struct Profile {
+    /// Topic of the controlled value
+    pub(crate) topic: String,
+    pub(crate) identifier: u64,
+    /// Timestamp, milliseconds, and value
+    pub(crate) points: Vec<[f64; 2]>,
+}
+
+/// Planned profile of the running recipe
+pub(crate) fn profile(context: &Context) -> Option<Profile> {
+    context.data(|data| data.get_temp(Id::new(PROFILE)))
+}
@@ -0,0 +1,280 @@
+use super::{YMDHMS, metadata::MetaDataFrame, mqtt, to_batch, topic, values};
+use anyhow::{Context as _, Result};
+use arrow::{
+    array::{AsArray as _, RecordBatch},
+    datatypes::TimestampMillisecondType,
+    temporal_conversions::timestamp_ms_to_datetime,
+};
+use egui::{ComboBox, Context, Grid, RichText, Slider, Ui, Window};
+use egui_l20n::{ResponseExt as _, UiExt as _};
+use egui_phosphor::regular::{PAUSE, PLAY, REPEAT, STOP};
+use polars::prelude::*;
+use std::{collections::BTreeMap, time::Duration};
+use tracing::instrument;
+
+const IDENTIFIER: &str = "Identifier";
+const TIMESTAMP: &str = "Timestamp";
+const FRAME: Duration = Duration::from_millis(50);
+
+/// Replay
+///
+/// Emits the rows of the loaded frames into the live path in timestamp order,
+/// as if they were received from the broker.
+#[derive(Debug, Default)]
+pub(crate) struct Replay {
+    pub(crate) open: bool,
+    pub(crate) speed: Speed,
+    pub(crate) looping: bool,
+    pub(crate) paused: bool,
+    streams: Vec<Stream>,
+    start: i64,
+    end: i64,
+    /// Replayed time, milliseconds
+    position: i64,
+    /// Input time of the last update, seconds
+    time: Option<f64>,
+}
+
+impl Replay {
+    /// Loads the frames, the frames of the same topic are merged. The topic is
+    /// derived from each value column, a wide frame feeds several topics.
+    #[instrument(skip_all, err)]
+    pub(crate) fn load(
+        &mut self,
+        context: &Context,
+        frames: impl IntoIterator<Item = MetaDataFrame>,
+    ) -> Result<()> {
+        let mut topics = BTreeMap::<_, Vec<_>>::new();
+        for frame in frames {
+            for column in values(&frame.data) {
+                let topic = topic(column.name());
+                // Device topics carry their value column name
+                let name = mqtt::name(&topic).unwrap_or(column.name().as_str());
+                let lazy_frame = frame.data.clone().lazy().select([
+                    col(IDENTIFIER),
+                    col(TIMESTAMP),
+                    col(column.name().clone()).alias(name),
+                ]);
+                topics.entry(topic).or_default().push(lazy_frame);
+            }
+        }
+        let mut streams = Vec::with_capacity(topics.len());
+        for (topic, frames) in topics {
+            let mut data = concat(frames, UnionArgs::default())?
+                .with_column(col(TIMESTAMP).cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+                .sort([TIMESTAMP], Default::default())
+                .collect()?;
+            streams.push(Stream::new(topic, &mut data)?);
+        }
+        self.stop(context);
+        self.start = streams
+            .iter()
+            .filter_map(|stream| stream.timestamps.first().copied())
+            .min()
+            .unwrap_or_default();
+        self.end = streams
+            .iter()
+            .filter_map(|stream| stream.timestamps.last().copied())
+            .max()
+            .unwrap_or_default();
+        self.position = self.start;
+        self.streams = streams;
+        self.paused = false;
+        self.open = true;
+        Ok(())
+    }
+
+    /// Advances the replayed time and emits the due rows
+    pub(crate) fn update(&mut self, context: &Context) {
+        if self.streams.is_empty() || self.paused {
+            self.time = None;
+            return;
+        }
+        let time = context.input(|input| input.time);
+        if let Some(last) = self.time.replace(time) {
+            let elapsed = (time - last) * 1000.0 * self.speed.factor();
+            self.position += elapsed as i64;
+        }
+        for stream in &mut self.streams {
+            stream.emit(context, self.position);
+        }
+        if self.position >= self.end {
+            if self.looping {
+                self.seek(context, self.start);
+            } else {
+                self.paused = true;
+            }
+        }
+        context.request_repaint_after(FRAME);
+    }
+
+    /// Moves the replayed time, the topic buffers are refilled from the start
+    pub(crate) fn seek(&mut self, context: &Context, position: i64) {
+        self.position = position.clamp(self.start, self.end);
+        for stream in &mut self.streams {
+            stream.cursor = 0;
+            mqtt::clear(context, &stream.topic);
+        }
+    }
+
+    pub(crate) fn stop(&mut self, context: &Context) {
+        for stream in self.streams.drain(..) {
+            mqtt::clear(context, &stream.topic);
+        }
+        self.time = None;
+    }
+}
+
+impl Replay {
+    pub(crate) fn window(&mut self, context: &Context) {
+        if self.streams.is_empty() {
+            return;
+        }
+        let mut open = self.open;
+        Window::new(format!("{PLAY} Replay"))
+            .open(&mut open)
+            .show(context, |ui| self.show(ui));
+        self.open = open;
+    }
+
+    fn show(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let text = if self.paused { PLAY } else { PAUSE };
+            if ui
+                .button(RichText::new(text).heading())
+                .on_hover_localized("replay__pause")
+                .clicked()
+            {
+                self.paused ^= true;
+                if !self.paused && self.position >= self.end {
+                    self.seek(ui.ctx(), self.start);
+                }
+            }
+            if ui
+                .button(RichText::new(STOP).heading())
+                .on_hover_localized("replay__stop")
+                .clicked()
+            {
+                self.stop(ui.ctx());
+                return;
+            }
+            ui.toggle_value(&mut self.looping, RichText::new(REPEAT).heading())
+                .on_hover_localized("replay__loop");
+            ComboBox::from_id_salt("Speed")
+                .selected_text(self.speed.text())
+                .show_ui(ui, |ui| {
+                    for speed in [Speed::X1, Speed::X10, Speed::X100] {
+                        ui.selectable_value(&mut self.speed, speed, speed.text());
+                    }
+                })
+                .response
+                .on_hover_localized("replay__speed");
+        });
+        // Seek
+        let mut position = self.position;
+        let response = ui
+            .add(
+                Slider::new(&mut position, self.start..=self.end)
+                    .show_value(false)
+                    .trailing_fill(true),
+            )
+            .on_hover_localized("replay__seek");
+        if response.changed() {
+            self.seek(ui.ctx(), position);
+        }
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            ui.label(ui.localize("replay__position"));
+            ui.label(format(self.position));
+            ui.end_row();
+            ui.label(ui.localize("replay__range"));
+            ui.label(format!("{} - {}", format(self.start), format(self.end)));
+            ui.end_row();
+            ui.label(ui.localize("replay__topics"));
+            ui.vertical(|ui| {
+                for stream in &self.streams {
+                    ui.label(format!(
+                        "{} ({}/{})",
+                        stream.topic,
+                        stream.cursor,
+                        stream.timestamps.len(),
+                    ));
+                }
+            });
+            ui.end_row();
+        });
+    }
+}
+
+/// Replay speed factor
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum Speed {
+    #[default]
+    X1,
+    X10,
+    X100,
+}
+
+impl Speed {
+    pub(crate) const fn factor(self) -> f64 {
+        match self {
+            Self::X1 => 1.0,
+            Self::X10 => 10.0,
+            Self::X100 => 100.0,
+        }
+    }
+
+    pub(crate) const fn text(self) -> &'static str {
+        match self {
+            Self::X1 => "1x",
+            Self::X10 => "10x",
+            Self::X100 => "100x",
+        }
+    }
+}
+
+/// Replayed topic
+#[derive(Debug)]
+struct Stream {
+    topic: String,
+    batch: RecordBatch,
+    timestamps: Vec<i64>,
+    /// Index of the next row to emit
+    cursor: usize,
+}
+
+impl Stream {
+    fn new(topic: String, data: &mut DataFrame) -> Result<Self> {
+        let batch = to_batch(data)?;
+        let timestamps = batch
+            .column_by_name(TIMESTAMP)
+            .context("No timestamp column")?
+            .as_primitive_opt::<TimestampMillisecondType>()
+            .context("Timestamp is not in milliseconds")?
+            .values()
+            .to_vec();
+        Ok(Self {
+            topic,
+            batch,
+            timestamps,
+            cursor: 0,
+        })
+    }
+
+    /// Emits the rows up to the position
+    fn emit(&mut self, context: &Context, position: i64) {
+        let end = self.cursor
+            + self.timestamps[self.cursor..].partition_point(|&timestamp| timestamp <= position);
+        if end > self.cursor {
+            let batch = self.batch.slice(self.cursor, end - self.cursor);
+            mqtt::topics(context).lock().record(&self.topic, Ok(&batch));
+            mqtt::feed(context, &self.topic, batch);
+            self.cursor = end;
+        }
+    }
+}
+
+fn format(timestamp: i64) -> String {
+    timestamp_ms_to_datetime(timestamp).map_or_else(String::new, |timestamp| {
+        timestamp.format(YMDHMS).to_string()
+    })
+}
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use arrow::ipc::{reader::FileReader, writer::FileWriter};
+use polars::prelude::*;
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Write},
+};
+use tracing::instrument;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::native::save;
+#[cfg(target_arch = "wasm32")]
+pub use self::web::save;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use super::*;
+    use std::fs::File;
+
+    #[instrument(skip(meta), err)]
+    pub fn save(data_frame: &DataFrame, meta: &BTreeMap<String, String>, name: &str) -> Result<()> {
+        write(data_frame, meta, File::create(name)?)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use super::*;
+    use anyhow::bail;
+
+    #[instrument(skip(meta), err)]
+    pub fn save(data_frame: &DataFrame, meta: &BTreeMap<String, String>, name: &str) -> Result<()> {
+        let mut buffer = Vec::new();
+        write(data_frame, meta, &mut buffer)?;
+        if let Err(error) = super::super::save(&buffer, name, super::super::ARROW) {
+            bail!("save: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+/// Writes the Arrow IPC file format with the metadata in the schema
+fn write(
+    data_frame: &DataFrame,
+    meta: &BTreeMap<String, String>,
+    writer: impl Write,
+) -> Result<()> {
+    // The oldest level avoids the view types other Arrow tools may not read
+    let mut bytes = Vec::new();
+    IpcWriter::new(&mut bytes)
+        .with_compat_level(CompatLevel::oldest())
+        .finish(&mut data_frame.clone())?;
+    // Polars doesn't write the schema metadata, so the batches are rewritten
+    let reader = FileReader::try_new(Cursor::new(bytes), None)?;
+    let schema = Arc::new(
+        reader
+            .schema()
+            .as_ref()
+            .clone()
+            .with_metadata(meta.clone().into_iter().collect()),
+    );
+    let mut writer = FileWriter::try_new(writer, &schema)?;
+    for batch in reader {
+        writer.write(&batch?.with_schema(schema.clone())?)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
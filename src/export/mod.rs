@@ -1,5 +1,5 @@
 #[cfg(target_arch = "wasm32")]
-use self::web::save;
+use self::web::{ARROW, XLSX, save};
 
 // https://github.com/ippras-utca/utca/blob/bca91021413c4089f412d07267147db097c94eb6/src/widgets/file_dialog/mod.rs
 // https://stackoverflow.com/questions/69556755/web-sysurlcreate-object-url-with-blobblob-not-formatting-binary-data-co
@@ -14,13 +14,15 @@ mod web {
         Blob, BlobPropertyBag, Document, Element, HtmlAnchorElement, Url, Window, window,
     };
 
-    const XLSX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+    pub(super) const XLSX: &str =
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+    pub(super) const ARROW: &str = "application/vnd.apache.arrow.file";
     const _DOCX: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
     const _XLS: &str = "application/vnd.ms-excel";
     const _TYPE: &str = "application/octet-stream";
 
     #[instrument(err(Debug))]
-    pub(super) fn save(content: &[u8], name: &str) -> Result<(), JsValue> {
+    pub(super) fn save(content: &[u8], name: &str, mime: &str) -> Result<(), JsValue> {
         let Some(window) = window() else {
             return Err(JsError::new("window is none").into());
         };
@@ -32,7 +34,7 @@ mod web {
         array.push(&bytes.buffer());
         let blob = Blob::new_with_u8_array_sequence_and_options(
             &array,
-            BlobPropertyBag::new().type_(XLSX),
+            BlobPropertyBag::new().type_(mime),
         )?;
         let url = Url::create_object_url_with_blob(&blob)?;
         // window.location().set_href(&url)?;
@@ -45,4 +47,5 @@ mod web {
     }
 }
 
+pub mod ipc;
 pub mod xlsx;
@@ -2,6 +2,7 @@ use anyhow::Result;
 use arrow::temporal_conversions::*;
 use polars::prelude::*;
 use rust_xlsxwriter::{Format, Table, TableColumn, Workbook, worksheet::Worksheet};
+use std::collections::BTreeMap;
 use tracing::instrument;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -14,10 +15,13 @@ mod native {
     use super::*;
 
     #[cfg(not(target_arch = "wasm32"))]
-    #[instrument(err)]
-    pub fn save(data_frame: &DataFrame, name: &str) -> Result<()> {
+    #[instrument(skip(meta), err)]
+    pub fn save(data_frame: &DataFrame, meta: &BTreeMap<String, String>, name: &str) -> Result<()> {
         let mut workbook = Workbook::new();
         write(data_frame, workbook.add_worksheet())?;
+        if !meta.is_empty() {
+            metadata(meta, workbook.add_worksheet())?;
+        }
         workbook.save(name)?;
         Ok(())
     }
@@ -28,12 +32,15 @@ mod web {
     use super::*;
     use anyhow::bail;
 
-    #[instrument(err)]
-    pub fn save(data_frame: &DataFrame, name: &str) -> Result<()> {
+    #[instrument(skip(meta), err)]
+    pub fn save(data_frame: &DataFrame, meta: &BTreeMap<String, String>, name: &str) -> Result<()> {
         let mut workbook = Workbook::new();
         write(data_frame, workbook.add_worksheet())?;
+        if !meta.is_empty() {
+            metadata(meta, workbook.add_worksheet())?;
+        }
         let buffer = workbook.save_to_buffer()?;
-        if let Err(error) = super::super::save(&buffer, name) {
+        if let Err(error) = super::super::save(&buffer, name, super::super::XLSX) {
             bail!("save: {error:?}");
         }
         Ok(())
@@ -131,3 +138,14 @@ fn write(data_frame: &DataFrame, worksheet: &mut Worksheet) -> Result<()> {
     worksheet.autofit();
     Ok(())
 }
+
+/// Writes the key/value metadata to a separate worksheet
+fn metadata(meta: &BTreeMap<String, String>, worksheet: &mut Worksheet) -> Result<()> {
+    worksheet.set_name("Metadata")?;
+    for (row, (key, value)) in meta.iter().enumerate() {
+        worksheet.write_string(row as u32, 0, key)?;
+        worksheet.write_string(row as u32, 1, value)?;
+    }
+    worksheet.autofit();
+    Ok(())
+}